@@ -0,0 +1,84 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use utf8_chunked::Utf8Chunker;
+
+/// Size of each generated input, in bytes (approximately).
+const INPUT_LEN: usize = 64 * 1024;
+
+/// Chunk sizes to split inputs into. Odd sizes force multi-byte characters
+/// to straddle chunk boundaries; `usize::MAX` feeds the whole input at once.
+const CHUNK_SIZES: &[usize] = &[7, 1024, 4096, 4099, usize::MAX];
+
+fn repeat_to_len(unit: &str) -> Vec<u8> {
+    unit.repeat(INPUT_LEN / unit.len()).into_bytes()
+}
+
+fn inputs() -> Vec<(&'static str, Vec<u8>)> {
+    vec![
+        ("ascii", repeat_to_len("The quick brown fox jumps over the lazy dog. ")),
+        ("cjk", repeat_to_len("한국어 텍스트와 漢字, そして日本語のかな。")),
+        ("emoji", repeat_to_len("🦀🌍👨\u{200D}👩\u{200D}👧🎉 ok ")),
+    ]
+}
+
+fn decode_all(data: &[u8], chunk_size: usize) -> usize {
+    let mut chunker = Utf8Chunker::new();
+    let mut total = 0;
+    for chunk in data.chunks(chunk_size.min(data.len())) {
+        if let Some(s) = chunker.push(chunk) {
+            total += s.len();
+        }
+    }
+    if let Some(s) = chunker.flush() {
+        total += s.len();
+    }
+    total
+}
+
+fn bench_push(c: &mut Criterion) {
+    for (name, data) in inputs() {
+        let mut group = c.benchmark_group(name);
+        group.throughput(Throughput::Bytes(data.len() as u64));
+        for &chunk_size in CHUNK_SIZES {
+            let id = if chunk_size == usize::MAX {
+                BenchmarkId::from_parameter("whole")
+            } else {
+                BenchmarkId::from_parameter(chunk_size)
+            };
+            group.bench_with_input(id, &data, |b, data| {
+                b.iter(|| decode_all(black_box(data), chunk_size))
+            });
+        }
+        group.finish();
+    }
+}
+
+/// Splits every character at each possible byte offset: the worst case for
+/// the carry path.
+fn bench_split_patterns(c: &mut Criterion) {
+    let mut group = c.benchmark_group("split_patterns");
+    for (name, unit) in [("2byte", "é"), ("3byte", "한"), ("4byte", "🦀")] {
+        let data = repeat_to_len(unit);
+        group.throughput(Throughput::Bytes(data.len() as u64));
+        for offset in 1..unit.len() {
+            let id = BenchmarkId::new(name, format!("split_at_{offset}"));
+            group.bench_with_input(id, &data, |b, data| {
+                b.iter(|| {
+                    let mut chunker = Utf8Chunker::new();
+                    let mut total = 0;
+                    let (first, rest) = data.split_at(offset);
+                    chunker.push(black_box(first));
+                    for chunk in rest.chunks(unit.len()) {
+                        if let Some(s) = chunker.push(black_box(chunk)) {
+                            total += s.len();
+                        }
+                    }
+                    total
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_push, bench_split_patterns);
+criterion_main!(benches);
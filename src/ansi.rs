@@ -0,0 +1,504 @@
+//! ANSI escape sequence handling on top of [`Utf8Chunker`].
+//!
+//! A chunk boundary inside `ESC [ 3 1 m` breaks terminal renderers the same way
+//! a split code point does. [`AnsiChunker`] holds back incomplete CSI, OSC,
+//! DCS and other escape sequences until they are complete, and can optionally
+//! strip them. [`AnsiStyler`] goes one step further and parses SGR sequences
+//! into styled text spans.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::mem;
+
+use crate::{non_empty, Utf8Chunker};
+
+const ESC: u8 = 0x1B;
+const BEL: u8 = 0x07;
+
+/// Longest incomplete escape sequence that is held back between pushes.
+///
+/// An unterminated OSC or DCS string longer than this is released as-is
+/// rather than buffered indefinitely. When stripping, its payload is dropped
+/// as it arrives until the sequence ends.
+pub const MAX_PENDING_ESCAPE: usize = 4096;
+
+/// What [`AnsiChunker`] does with complete escape sequences.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum AnsiMode {
+    /// Pass escape sequences through unchanged.
+    #[default]
+    Keep,
+    /// Remove escape sequences from the output.
+    Strip,
+}
+
+/// Incremental UTF-8 decoder that never splits ANSI escape sequences.
+///
+/// # Examples
+///
+/// ```
+/// use utf8_chunked::{AnsiChunker, AnsiMode};
+///
+/// let mut chunker = AnsiChunker::new();
+///
+/// // "ESC [ 3 1 m" split after "ESC [ 3"
+/// assert_eq!(chunker.push(b"ok \x1b[3"), Some("ok ".to_string()));
+/// assert_eq!(chunker.push(b"1mred"), Some("\x1b[31mred".to_string()));
+///
+/// let mut stripper = AnsiChunker::with_mode(AnsiMode::Strip);
+/// assert_eq!(stripper.push(b"\x1b[1mbold\x1b[0m"), Some("bold".to_string()));
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct AnsiChunker {
+    chunker: Utf8Chunker,
+    /// An escape sequence that has started but not yet ended.
+    pending: String,
+    mode: AnsiMode,
+}
+
+impl AnsiChunker {
+    /// Creates a new `AnsiChunker` that keeps escape sequences.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new `AnsiChunker` with the given mode.
+    pub fn with_mode(mode: AnsiMode) -> Self {
+        Self {
+            mode,
+            ..Self::default()
+        }
+    }
+
+    /// Processes an incoming byte chunk and returns any complete text.
+    ///
+    /// Returns `None` if everything is held back as part of an incomplete
+    /// character or escape sequence.
+    pub fn push(&mut self, data: &[u8]) -> Option<String> {
+        let text = self.chunker.push(data)?;
+        let mut input = mem::take(&mut self.pending);
+        input.push_str(&text);
+
+        let mut out = String::with_capacity(input.len());
+        let bytes = input.as_bytes();
+        let mut start = 0;
+
+        while let Some(offset) = bytes[start..].iter().position(|&b| b == ESC) {
+            let esc = start + offset;
+            out.push_str(&input[start..esc]);
+
+            match escape_len(&bytes[esc..]) {
+                Some(len) => {
+                    if self.mode == AnsiMode::Keep {
+                        out.push_str(&input[esc..esc + len]);
+                    }
+                    start = esc + len;
+                }
+                None if bytes.len() - esc <= MAX_PENDING_ESCAPE => {
+                    self.pending.push_str(&input[esc..]);
+                    start = bytes.len();
+                }
+                None => {
+                    // Too long to hold back
+                    match self.mode {
+                        // Give up on it as an escape
+                        AnsiMode::Keep => out.push_str(&input[esc..]),
+                        // Drop the payload so far, but keep the introducer so
+                        // the rest is dropped up to the terminator
+                        AnsiMode::Strip => self.pending.push_str(&input[esc..esc + 2]),
+                    }
+                    start = bytes.len();
+                }
+            }
+        }
+        out.push_str(&input[start..]);
+
+        non_empty(out)
+    }
+
+    /// Flushes any held-back bytes at the end of the stream.
+    ///
+    /// An incomplete character is replaced with U+FFFD; an incomplete escape
+    /// sequence is emitted as-is, or dropped in [`AnsiMode::Strip`].
+    pub fn flush(&mut self) -> Option<String> {
+        let mut out = mem::take(&mut self.pending);
+        if self.mode == AnsiMode::Strip {
+            out.clear();
+        }
+        if let Some(text) = self.chunker.flush() {
+            out.push_str(&text);
+        }
+        non_empty(out)
+    }
+
+    /// Returns `true` if no partial character or escape sequence is held back.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.chunker.is_empty() && self.pending.is_empty()
+    }
+}
+
+/// Returns the length of the complete escape sequence at the start of `b`, or
+/// `None` if more bytes are needed. `b[0]` must be ESC.
+fn escape_len(b: &[u8]) -> Option<usize> {
+    let kind = *b.get(1)?;
+    match kind {
+        // CSI: parameter bytes, intermediate bytes, then a final byte
+        b'[' => {
+            for (i, &c) in b.iter().enumerate().skip(2) {
+                match c {
+                    0x20..=0x3F => {}
+                    0x40..=0x7E => return Some(i + 1),
+                    // Malformed; end the sequence before the offending byte
+                    _ => return Some(i),
+                }
+            }
+            None
+        }
+        // OSC, DCS, SOS, PM, APC: a string terminated by ST (or BEL for OSC)
+        b']' | b'P' | b'X' | b'^' | b'_' => {
+            for (i, &c) in b.iter().enumerate().skip(2) {
+                if c == BEL && kind == b']' {
+                    return Some(i + 1);
+                }
+                if c == ESC {
+                    return match b.get(i + 1) {
+                        Some(b'\\') => Some(i + 2),
+                        // Aborted by the start of another sequence
+                        Some(_) => Some(i),
+                        None => None,
+                    };
+                }
+            }
+            None
+        }
+        // nF sequences: intermediate bytes, then a final byte
+        0x20..=0x2F => {
+            for (i, &c) in b.iter().enumerate().skip(2) {
+                match c {
+                    0x20..=0x2F => {}
+                    0x30..=0x7E => return Some(i + 1),
+                    _ => return Some(i),
+                }
+            }
+            None
+        }
+        // Two-byte sequences such as `ESC c` or `ESC 7`
+        0x30..=0x7E => Some(2),
+        // A lone ESC
+        _ => Some(1),
+    }
+}
+
+/// A terminal colour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AnsiColor {
+    /// A palette index: 0–7 standard, 8–15 bright, 16–255 extended.
+    Indexed(u8),
+    /// A 24-bit colour.
+    Rgb(u8, u8, u8),
+}
+
+/// Text attributes set by SGR (`ESC [ ... m`) sequences.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AnsiStyle {
+    /// Foreground colour, or `None` for the terminal default.
+    pub fg: Option<AnsiColor>,
+    /// Background colour, or `None` for the terminal default.
+    pub bg: Option<AnsiColor>,
+    /// SGR 1.
+    pub bold: bool,
+    /// SGR 2.
+    pub dim: bool,
+    /// SGR 3.
+    pub italic: bool,
+    /// SGR 4.
+    pub underline: bool,
+    /// SGR 7: swap foreground and background.
+    pub inverse: bool,
+    /// SGR 9.
+    pub strikethrough: bool,
+}
+
+impl AnsiStyle {
+    /// Applies the parameters of one SGR sequence.
+    fn apply_sgr(&mut self, params: &str) {
+        let mut params = params
+            .split([';', ':'])
+            .map(|p| p.parse::<u16>().unwrap_or(0));
+
+        while let Some(code) = params.next() {
+            match code {
+                0 => *self = AnsiStyle::default(),
+                1 => self.bold = true,
+                2 => self.dim = true,
+                3 => self.italic = true,
+                4 => self.underline = true,
+                7 => self.inverse = true,
+                9 => self.strikethrough = true,
+                22 => {
+                    self.bold = false;
+                    self.dim = false;
+                }
+                23 => self.italic = false,
+                24 => self.underline = false,
+                27 => self.inverse = false,
+                29 => self.strikethrough = false,
+                30..=37 => self.fg = Some(AnsiColor::Indexed((code - 30) as u8)),
+                38 => self.fg = extended_color(&mut params),
+                39 => self.fg = None,
+                40..=47 => self.bg = Some(AnsiColor::Indexed((code - 40) as u8)),
+                48 => self.bg = extended_color(&mut params),
+                49 => self.bg = None,
+                90..=97 => self.fg = Some(AnsiColor::Indexed((code - 90 + 8) as u8)),
+                100..=107 => self.bg = Some(AnsiColor::Indexed((code - 100 + 8) as u8)),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Parses the `5;n` or `2;r;g;b` tail of an SGR 38/48 parameter.
+fn extended_color(params: &mut impl Iterator<Item = u16>) -> Option<AnsiColor> {
+    let mut next = || params.next().map(|p| p.min(255) as u8);
+    match next()? {
+        5 => Some(AnsiColor::Indexed(next()?)),
+        2 => Some(AnsiColor::Rgb(next()?, next()?, next()?)),
+        _ => None,
+    }
+}
+
+/// A run of text with a single style.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnsiSpan {
+    /// The text, with all escape sequences removed.
+    pub text: String,
+    /// The style in effect for the whole span.
+    pub style: AnsiStyle,
+}
+
+/// Incremental decoder that turns ANSI-coloured output into styled spans.
+///
+/// SGR sequences update the current style, which carries over between pushes;
+/// all other escape sequences are dropped.
+///
+/// # Examples
+///
+/// ```
+/// use utf8_chunked::{AnsiColor, AnsiStyler};
+///
+/// let mut styler = AnsiStyler::new();
+/// let spans = styler.push(b"plain \x1b[1;31mbold red\x1b[0m");
+///
+/// assert_eq!(spans[0].text, "plain ");
+/// assert_eq!(spans[1].text, "bold red");
+/// assert!(spans[1].style.bold);
+/// assert_eq!(spans[1].style.fg, Some(AnsiColor::Indexed(1)));
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct AnsiStyler {
+    chunker: AnsiChunker,
+    style: AnsiStyle,
+}
+
+impl AnsiStyler {
+    /// Creates a new `AnsiStyler` starting with the default style.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Processes an incoming byte chunk and returns the styled spans it completed.
+    pub fn push(&mut self, data: &[u8]) -> Vec<AnsiSpan> {
+        match self.chunker.push(data) {
+            Some(text) => self.spans(&text),
+            None => Vec::new(),
+        }
+    }
+
+    /// Flushes any held-back bytes at the end of the stream.
+    pub fn flush(&mut self) -> Vec<AnsiSpan> {
+        match self.chunker.flush() {
+            Some(text) => self.spans(&text),
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns the style that will apply to the next text.
+    #[inline]
+    pub fn style(&self) -> AnsiStyle {
+        self.style
+    }
+
+    /// Splits text containing only complete escape sequences into spans.
+    fn spans(&mut self, text: &str) -> Vec<AnsiSpan> {
+        let mut spans: Vec<AnsiSpan> = Vec::new();
+        let bytes = text.as_bytes();
+        let mut start = 0;
+
+        loop {
+            let esc = bytes[start..]
+                .iter()
+                .position(|&b| b == ESC)
+                .map_or(bytes.len(), |offset| start + offset);
+
+            if esc > start {
+                let segment = &text[start..esc];
+                match spans.last_mut() {
+                    Some(last) if last.style == self.style => last.text.push_str(segment),
+                    _ => spans.push(AnsiSpan {
+                        text: String::from(segment),
+                        style: self.style,
+                    }),
+                }
+            }
+            if esc == bytes.len() {
+                break;
+            }
+
+            let len = escape_len(&bytes[esc..]).unwrap_or(bytes.len() - esc);
+            let seq = &text[esc..esc + len];
+            if let Some(params) = seq.strip_prefix("\x1b[").and_then(|s| s.strip_suffix('m')) {
+                self.style.apply_sgr(params);
+            }
+            start = esc + len;
+        }
+
+        spans
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_all(chunker: &mut AnsiChunker, chunks: &[&[u8]]) -> String {
+        let mut out = String::new();
+        for chunk in chunks {
+            if let Some(s) = chunker.push(chunk) {
+                out.push_str(&s);
+            }
+        }
+        if let Some(s) = chunker.flush() {
+            out.push_str(&s);
+        }
+        out
+    }
+
+    #[test]
+    fn csi_split_at_every_byte() {
+        let input = "a\x1b[38;5;196m한\x1b[0mb".as_bytes();
+        for split in 1..input.len() {
+            let mut c = AnsiChunker::new();
+            let (first, second) = input.split_at(split);
+            let mut out = c.push(first).unwrap_or_default();
+            // Each emitted piece must not end inside an escape sequence
+            if let Some(esc) = out.rfind('\x1b') {
+                assert!(
+                    escape_len(&out.as_bytes()[esc..]).is_some(),
+                    "split={split}"
+                );
+            }
+            out.push_str(&push_all(&mut c, &[second]));
+            assert_eq!(out, "a\x1b[38;5;196m한\x1b[0mb", "split={split}");
+        }
+    }
+
+    #[test]
+    fn osc_terminated_by_bel_and_st() {
+        let mut c = AnsiChunker::new();
+        assert_eq!(c.push(b"\x1b]0;title"), None);
+        assert_eq!(c.push(b"\x07x"), Some("\x1b]0;title\x07x".into()));
+        assert_eq!(c.push(b"\x1b]8;;http://a\x1b"), None);
+        assert_eq!(c.push(b"\\link"), Some("\x1b]8;;http://a\x1b\\link".into()));
+    }
+
+    #[test]
+    fn dcs_held_until_st() {
+        let mut c = AnsiChunker::with_mode(AnsiMode::Strip);
+        assert_eq!(c.push(b"x\x1bPq#0;2;0;0;0"), Some("x".into()));
+        assert_eq!(c.push(b"~-\x1b\\y"), Some("y".into()));
+    }
+
+    #[test]
+    fn lone_esc_held_back() {
+        let mut c = AnsiChunker::new();
+        assert_eq!(c.push(b"ab\x1b"), Some("ab".into()));
+        assert!(!c.is_empty());
+        assert_eq!(c.push(b"[K"), Some("\x1b[K".into()));
+    }
+
+    #[test]
+    fn strip_mode() {
+        let mut c = AnsiChunker::with_mode(AnsiMode::Strip);
+        assert_eq!(
+            push_all(
+                &mut c,
+                &[b"\x1b[1", b";32mgreen\x1b(B\x1b]0;t\x07 \x1b[0m\x1b["]
+            ),
+            "green "
+        );
+    }
+
+    #[test]
+    fn unterminated_escape_released_after_limit() {
+        let mut c = AnsiChunker::new();
+        assert_eq!(c.push(b"\x1b]0;"), None);
+        let long = [b'x'; MAX_PENDING_ESCAPE];
+        let out = c.push(&long).unwrap();
+        assert!(out.starts_with("\x1b]0;x"));
+        assert!(c.is_empty());
+
+        // Stripping drops the whole payload, however long, up to ST or BEL
+        let mut c = AnsiChunker::with_mode(AnsiMode::Strip);
+        let mut out = String::new();
+        for chunk in [
+            &b"a\x1b]0;"[..],
+            &long,
+            &long,
+            b"xx\x07b\x1bPq",
+            &long,
+            b"x\x1b",
+            b"\\c",
+        ] {
+            out.extend(c.push(chunk));
+        }
+        out.extend(c.flush());
+        assert_eq!(out, "abc");
+        assert!(c.is_empty());
+    }
+
+    #[test]
+    fn flush_emits_incomplete_escape() {
+        let mut c = AnsiChunker::new();
+        assert_eq!(c.push(b"\x1b[3"), None);
+        assert_eq!(c.flush(), Some("\x1b[3".into()));
+    }
+
+    #[test]
+    fn styler_tracks_sgr_across_pushes() {
+        let mut s = AnsiStyler::new();
+        let spans = s.push(b"\x1b[1;3");
+        assert!(spans.is_empty());
+        let spans = s.push(b"8;2;255;128;0mhot\x1b[22");
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].text, "hot");
+        assert!(spans[0].style.bold);
+        assert_eq!(spans[0].style.fg, Some(AnsiColor::Rgb(255, 128, 0)));
+
+        let spans = s.push(b"m cold\x1b[K\x1b[44m!\x1b[m");
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].text, " cold");
+        assert!(!spans[0].style.bold);
+        assert_eq!(spans[1].style.bg, Some(AnsiColor::Indexed(4)));
+        assert_eq!(s.style(), AnsiStyle::default());
+    }
+
+    #[test]
+    fn styler_merges_same_style_text() {
+        let mut s = AnsiStyler::new();
+        let spans = s.push(b"a\x1b[Kb\x1b[91mc");
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].text, "ab");
+        assert_eq!(spans[1].style.fg, Some(AnsiColor::Indexed(9)));
+    }
+}
@@ -0,0 +1,197 @@
+//! Fan-out of decoded text to multiple subscribers (requires the `tokio`
+//! feature).
+
+use std::collections::VecDeque;
+use std::io;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use tokio::io::AsyncRead;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tokio_stream::StreamExt;
+use tokio_util::codec::FramedRead;
+
+use crate::{Utf8Bytes, Utf8BytesCodec};
+
+/// An item received by a [`TextSubscriber`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TextEvent {
+    /// Decoded text, always made of whole characters.
+    ///
+    /// Every subscriber shares the same buffer, so receiving it does not copy
+    /// the text.
+    Text(Utf8Bytes),
+    /// The subscriber fell behind and this many pieces of text were skipped.
+    Lagged(u64),
+}
+
+/// State shared between the reading task and [`TextBroadcaster::subscribe`].
+///
+/// Appending to the replay buffer and sending happen under the same lock as
+/// subscribing, so a new subscriber sees every piece exactly once: either in
+/// its replay or on the channel.
+#[derive(Debug)]
+struct Shared {
+    /// `None` once the source has ended.
+    sender: Option<broadcast::Sender<Utf8Bytes>>,
+    replay: VecDeque<Utf8Bytes>,
+    replay_len: usize,
+    replay_limit: usize,
+}
+
+impl Shared {
+    fn publish(&mut self, text: Utf8Bytes) {
+        self.replay_len += text.len();
+        self.replay.push_back(text.clone());
+        while self.replay_len > self.replay_limit {
+            match self.replay.pop_front() {
+                Some(old) => self.replay_len -= old.len(),
+                None => break,
+            }
+        }
+        if let Some(sender) = &self.sender {
+            // No subscribers is not an error: the text is still in the replay
+            let _ = sender.send(text);
+        }
+    }
+}
+
+/// Decodes a reader with [`Utf8BytesCodec`] on a background task and fans its
+/// text out to any number of subscribers.
+///
+/// Text is shared as [`Utf8Bytes`], so handing a piece to every subscriber
+/// and keeping it for replay only bumps a reference count.
+///
+/// Each subscriber first receives a replay of the most recent text (up to
+/// `replay_bytes`, evicting whole pieces), then everything decoded after it
+/// subscribed. A subscriber that falls more than `capacity` pieces behind
+/// receives [`TextEvent::Lagged`] and continues with the oldest text still
+/// buffered.
+///
+/// Dropping the `TextBroadcaster` aborts the reading task; subscribers then
+/// see the end of the text once they have received what was already sent.
+///
+/// # Examples
+///
+/// ```no_run
+/// use utf8_chunked::{TextBroadcaster, TextEvent};
+///
+/// # async fn example(child_stdout: tokio::process::ChildStdout) -> std::io::Result<()> {
+/// let broadcaster = TextBroadcaster::spawn(child_stdout, 1024, 64 * 1024);
+///
+/// let mut pane = broadcaster.subscribe();
+/// tokio::spawn(async move {
+///     while let Some(event) = pane.recv().await {
+///         match event {
+///             TextEvent::Text(text) => print!("{text}"),
+///             TextEvent::Lagged(n) => eprintln!("[skipped {n} chunks]"),
+///         }
+///     }
+/// });
+///
+/// broadcaster.join().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct TextBroadcaster {
+    shared: Arc<Mutex<Shared>>,
+    task: JoinHandle<io::Result<()>>,
+}
+
+impl TextBroadcaster {
+    /// Spawns a task that decodes `reader` and broadcasts its text.
+    ///
+    /// `capacity` is the number of pieces buffered for slow subscribers, and
+    /// `replay_bytes` bounds the recent text replayed to new subscribers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is 0, or if called outside a Tokio runtime.
+    pub fn spawn<R>(reader: R, capacity: usize, replay_bytes: usize) -> Self
+    where
+        R: AsyncRead + Unpin + Send + 'static,
+    {
+        let (sender, _) = broadcast::channel(capacity);
+        let shared = Arc::new(Mutex::new(Shared {
+            sender: Some(sender),
+            replay: VecDeque::new(),
+            replay_len: 0,
+            replay_limit: replay_bytes,
+        }));
+
+        let task_shared = Arc::clone(&shared);
+        let task = tokio::spawn(async move {
+            let mut stream = FramedRead::new(reader, Utf8BytesCodec::new());
+            let result = loop {
+                match stream.next().await {
+                    Some(Ok(text)) => lock(&task_shared).publish(text),
+                    Some(Err(e)) => break Err(e),
+                    None => break Ok(()),
+                }
+            };
+            // Dropping the sender lets subscribers see the end of the stream
+            lock(&task_shared).sender = None;
+            result
+        });
+
+        Self { shared, task }
+    }
+
+    /// Subscribes to the text, starting with a replay of recent text.
+    ///
+    /// Subscribing after the source has ended still yields the replay.
+    pub fn subscribe(&self) -> TextSubscriber {
+        let shared = lock(&self.shared);
+        TextSubscriber {
+            replay: shared.replay.clone(),
+            receiver: shared.sender.as_ref().map(broadcast::Sender::subscribe),
+        }
+    }
+
+    /// Returns `true` once the source has ended or failed.
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+
+    /// Waits for the source to end, returning its read error, if any.
+    pub async fn join(mut self) -> io::Result<()> {
+        (&mut self.task).await.map_err(io::Error::other)?
+    }
+}
+
+impl Drop for TextBroadcaster {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// A subscription created by [`TextBroadcaster::subscribe`].
+#[derive(Debug)]
+pub struct TextSubscriber {
+    replay: VecDeque<Utf8Bytes>,
+    receiver: Option<broadcast::Receiver<Utf8Bytes>>,
+}
+
+impl TextSubscriber {
+    /// Receives the next event, or `None` once the source has ended and all
+    /// text has been received.
+    pub async fn recv(&mut self) -> Option<TextEvent> {
+        if let Some(text) = self.replay.pop_front() {
+            return Some(TextEvent::Text(text));
+        }
+        match self.receiver.as_mut()?.recv().await {
+            Ok(text) => Some(TextEvent::Text(text)),
+            Err(broadcast::error::RecvError::Lagged(n)) => Some(TextEvent::Lagged(n)),
+            Err(broadcast::error::RecvError::Closed) => {
+                self.receiver = None;
+                None
+            }
+        }
+    }
+}
+
+/// Locks `shared`, ignoring poisoning: the state is consistent between calls.
+fn lock(shared: &Mutex<Shared>) -> MutexGuard<'_, Shared> {
+    shared.lock().unwrap_or_else(|e| e.into_inner())
+}
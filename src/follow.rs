@@ -0,0 +1,226 @@
+//! `tail -f`-style following of a growing file (requires the `tokio` feature).
+
+use std::fs::Metadata;
+use std::io::{self, SeekFrom};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use tokio::fs::{self, File};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio_stream::Stream;
+
+use crate::{non_empty, Utf8Chunker};
+
+/// Default delay between checks for new data.
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Follows a growing file, decoding appended bytes as UTF-8.
+///
+/// Every appended byte is fed through a [`Utf8Chunker`], so a writer that
+/// flushes half of a multi-byte character before its next write never
+/// produces a broken character. The file is polled for new data, which works
+/// on every platform and file system.
+///
+/// Like `tail -F`, the follower survives log rotation:
+///
+/// - If the file shrinks below the current position, it is treated as
+///   truncated and read again from the start.
+/// - On Unix, if the path is replaced by a different file, the follower
+///   switches to the new file and reads it from the start.
+///
+/// In both cases the chunker's carry is reset, so an incomplete character at
+/// the end of the old contents cannot be joined with the new ones. A file that
+/// does not exist yet is waited for.
+///
+/// # Examples
+///
+/// ```no_run
+/// use tokio_stream::StreamExt;
+/// use utf8_chunked::FileFollower;
+///
+/// # async fn example() -> std::io::Result<()> {
+/// let stream = FileFollower::new("/var/log/app.log").into_stream();
+/// tokio::pin!(stream);
+/// while let Some(text) = stream.next().await {
+///     print!("{}", text?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct FileFollower {
+    path: PathBuf,
+    file: Option<File>,
+    /// Offset of the next byte to read from `file`.
+    pos: u64,
+    chunker: Utf8Chunker,
+    interval: Duration,
+    /// Whether the first open of the file starts at its end.
+    seek_to_end: bool,
+    buf: Vec<u8>,
+}
+
+impl FileFollower {
+    /// Creates a follower for `path` that starts at the current end of the
+    /// file, like `tail -f -n 0`.
+    ///
+    /// Nothing is opened until the first call to
+    /// [`next_text`](Self::next_text).
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            file: None,
+            pos: 0,
+            chunker: Utf8Chunker::new(),
+            interval: DEFAULT_POLL_INTERVAL,
+            seek_to_end: true,
+            buf: vec![0; 8 * 1024],
+        }
+    }
+
+    /// Reads the existing contents of the file first instead of starting at
+    /// its end.
+    pub fn from_start(mut self, from_start: bool) -> Self {
+        self.seek_to_end = !from_start;
+        self
+    }
+
+    /// Sets how long to wait between checks for new data (250 ms by default).
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Returns the offset in the current file of the next byte to be read.
+    #[inline]
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+
+    /// Waits until new text has been appended and returns it.
+    ///
+    /// Never returns an empty string; bytes that only start a multi-byte
+    /// character are held back until the rest is written. At most about one
+    /// read buffer (8 KiB) of text is returned per call, so a large backlog
+    /// is delivered over several calls without waiting between them.
+    pub async fn next_text(&mut self) -> io::Result<String> {
+        loop {
+            if let Some(text) = self.poll_once().await? {
+                return Ok(text);
+            }
+            tokio::time::sleep(self.interval).await;
+        }
+    }
+
+    /// Converts the follower into an endless stream of appended text.
+    pub fn into_stream(self) -> impl Stream<Item = io::Result<String>> {
+        futures::stream::unfold(self, |mut follower| async move {
+            let text = follower.next_text().await;
+            Some((text, follower))
+        })
+    }
+
+    /// Reads whatever is available without waiting.
+    async fn poll_once(&mut self) -> io::Result<Option<String>> {
+        let file = match &mut self.file {
+            Some(file) => file,
+            None => match File::open(&self.path).await {
+                Ok(mut file) => {
+                    self.pos = if self.seek_to_end {
+                        file.seek(SeekFrom::End(0)).await?
+                    } else {
+                        0
+                    };
+                    self.seek_to_end = false;
+                    self.file.insert(file)
+                }
+                Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+                Err(e) => return Err(e),
+            },
+        };
+
+        let mut out = String::new();
+        let at_end = read_available(
+            file,
+            &mut self.buf,
+            &mut self.chunker,
+            &mut self.pos,
+            &mut out,
+        )
+        .await?;
+        if !at_end {
+            // More is already available; the next call continues from here
+            return Ok(Some(out));
+        }
+
+        let current = file.metadata().await?;
+        if current.len() < self.pos {
+            // Truncated in place (e.g. `copytruncate`): start over
+            self.chunker.reset();
+            self.pos = file.seek(SeekFrom::Start(0)).await?;
+            read_available(
+                file,
+                &mut self.buf,
+                &mut self.chunker,
+                &mut self.pos,
+                &mut out,
+            )
+            .await?;
+        } else {
+            match fs::metadata(&self.path).await {
+                Ok(at_path) if !is_same_file(&current, &at_path) => {
+                    // Rotated: the old file is complete, so the next poll
+                    // opens the new one from its start
+                    self.chunker.reset();
+                    self.file = None;
+                    self.pos = 0;
+                }
+                // Same file, or moved away with no replacement yet: keep
+                // reading the open file
+                _ => {}
+            }
+        }
+
+        Ok(non_empty(out))
+    }
+}
+
+/// Reads `file` towards its current end, decoding into `out`.
+///
+/// Stops once about `buf.len()` bytes have been read and decoded into some
+/// text, so one call never holds a whole backlog in memory. Returns `true` if
+/// the end of the file was reached.
+async fn read_available(
+    file: &mut File,
+    buf: &mut [u8],
+    chunker: &mut Utf8Chunker,
+    pos: &mut u64,
+    out: &mut String,
+) -> io::Result<bool> {
+    let mut read = 0;
+    loop {
+        if read >= buf.len() && !out.is_empty() {
+            return Ok(false);
+        }
+        let n = file.read(buf).await?;
+        if n == 0 {
+            return Ok(true);
+        }
+        read += n;
+        *pos += n as u64;
+        if let Some(text) = chunker.push(&buf[..n]) {
+            out.push_str(&text);
+        }
+    }
+}
+
+#[cfg(unix)]
+fn is_same_file(a: &Metadata, b: &Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    a.dev() == b.dev() && a.ino() == b.ino()
+}
+
+#[cfg(not(unix))]
+fn is_same_file(_a: &Metadata, _b: &Metadata) -> bool {
+    true
+}
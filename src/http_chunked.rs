@@ -0,0 +1,368 @@
+//! Decoding of HTTP/1.1 `Transfer-Encoding: chunked` bodies to UTF-8 text.
+//!
+//! Chunk boundaries are chosen by the server without regard for character
+//! boundaries, so the data of consecutive chunks is decoded with a single
+//! [`Utf8Chunker`].
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{non_empty, Utf8Chunker};
+
+/// Maximum length of a trailer line, excluding its line ending.
+const MAX_TRAILER_LINE: usize = 8 * 1024;
+
+/// Error returned by [`ChunkedBodyDecoder::push`] for a malformed body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkedBodyError {
+    /// A chunk-size line does not start with a hexadecimal size.
+    InvalidChunkSize,
+    /// A chunk size does not fit in a `u64`.
+    ChunkSizeOverflow,
+    /// Chunk data is not followed by CRLF.
+    MissingCrlf,
+    /// A trailer line is not of the form `name: value`.
+    InvalidTrailer,
+    /// A trailer line is longer than 8 KiB.
+    TrailerTooLong,
+}
+
+impl fmt::Display for ChunkedBodyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ChunkedBodyError::InvalidChunkSize => "invalid chunk size",
+            ChunkedBodyError::ChunkSizeOverflow => "chunk size too large",
+            ChunkedBodyError::MissingCrlf => "missing CRLF after chunk data",
+            ChunkedBodyError::InvalidTrailer => "invalid trailer field",
+            ChunkedBodyError::TrailerTooLong => "trailer line too long",
+        })
+    }
+}
+
+impl core::error::Error for ChunkedBodyError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Reading the hex digits of a chunk size.
+    Size {
+        size: u64,
+        digits: bool,
+    },
+    /// Skipping chunk extensions up to the end of the size line.
+    Extension {
+        size: u64,
+    },
+    /// Saw CR at the end of the size line.
+    SizeLf {
+        size: u64,
+    },
+    Data {
+        remaining: u64,
+    },
+    DataCr,
+    DataLf,
+    /// Reading trailer fields after the last chunk.
+    Trailer,
+    Done,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State::Size {
+            size: 0,
+            digits: false,
+        }
+    }
+}
+
+/// Sans-IO decoder for `Transfer-Encoding: chunked` bodies carrying UTF-8 text.
+///
+/// Feed it raw body bytes (everything after the response headers) in chunks of
+/// any size; it parses chunk-size lines, skips chunk extensions, collects
+/// trailer fields and returns the decoded text. Bare LF line endings are
+/// accepted in place of CRLF.
+///
+/// Memory use is bounded for untrusted input: chunk extensions are skipped
+/// without being buffered, and a trailer line longer than 8 KiB is rejected
+/// with [`ChunkedBodyError::TrailerTooLong`].
+///
+/// # Examples
+///
+/// ```
+/// use utf8_chunked::ChunkedBodyDecoder;
+///
+/// let mut decoder = ChunkedBodyDecoder::new();
+///
+/// // '한' = ED 95 9C, split across two HTTP chunks
+/// let body = b"3;name=v\r\nhi\xED\r\n2\r\n\x95\x9C\r\n0\r\nX-Checksum: 1\r\n\r\n";
+/// assert_eq!(decoder.push(body), Ok(Some("hi한".to_string())));
+/// assert!(decoder.is_done());
+/// assert_eq!(decoder.trailers()[0], ("X-Checksum".to_string(), "1".to_string()));
+/// ```
+#[derive(Debug, Default)]
+pub struct ChunkedBodyDecoder {
+    state: State,
+    chunker: Utf8Chunker,
+    /// The trailer line currently being received.
+    line: Vec<u8>,
+    trailers: Vec<(String, String)>,
+    /// Bytes received after the end of the body.
+    remainder: Vec<u8>,
+}
+
+impl ChunkedBodyDecoder {
+    /// Creates a new `ChunkedBodyDecoder`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Processes raw body bytes and returns any decoded text.
+    ///
+    /// When the terminating zero-size chunk and trailer section have been read,
+    /// an incomplete character at the end of the body is flushed as U+FFFD and
+    /// any further bytes are kept in [`remainder`](Self::remainder).
+    pub fn push(&mut self, mut data: &[u8]) -> Result<Option<String>, ChunkedBodyError> {
+        let mut out = String::new();
+
+        while let Some((&byte, rest)) = data.split_first() {
+            match self.state {
+                State::Data { remaining } => {
+                    let len = data
+                        .len()
+                        .min(usize::try_from(remaining).unwrap_or(usize::MAX));
+                    if let Some(s) = self.chunker.push(&data[..len]) {
+                        out.push_str(&s);
+                    }
+                    data = &data[len..];
+                    self.state = match remaining - len as u64 {
+                        0 => State::DataCr,
+                        remaining => State::Data { remaining },
+                    };
+                    continue;
+                }
+                State::Done => {
+                    self.remainder.extend_from_slice(data);
+                    break;
+                }
+                State::Size { size, digits } => match byte {
+                    b'0'..=b'9' | b'a'..=b'f' | b'A'..=b'F' => {
+                        let digit = (byte as char).to_digit(16).unwrap_or(0) as u64;
+                        let size = size
+                            .checked_mul(16)
+                            .and_then(|s| s.checked_add(digit))
+                            .ok_or(ChunkedBodyError::ChunkSizeOverflow)?;
+                        self.state = State::Size { size, digits: true };
+                    }
+                    _ if !digits => return Err(ChunkedBodyError::InvalidChunkSize),
+                    b';' | b' ' | b'\t' => self.state = State::Extension { size },
+                    b'\r' => self.state = State::SizeLf { size },
+                    b'\n' => self.end_size_line(size),
+                    _ => return Err(ChunkedBodyError::InvalidChunkSize),
+                },
+                State::Extension { size } => match byte {
+                    b'\r' => self.state = State::SizeLf { size },
+                    b'\n' => self.end_size_line(size),
+                    _ => {}
+                },
+                State::SizeLf { size } => match byte {
+                    b'\n' => self.end_size_line(size),
+                    _ => return Err(ChunkedBodyError::InvalidChunkSize),
+                },
+                State::DataCr => match byte {
+                    b'\r' => self.state = State::DataLf,
+                    b'\n' => self.state = State::default(),
+                    _ => return Err(ChunkedBodyError::MissingCrlf),
+                },
+                State::DataLf => match byte {
+                    b'\n' => self.state = State::default(),
+                    _ => return Err(ChunkedBodyError::MissingCrlf),
+                },
+                State::Trailer => {
+                    if byte == b'\n' {
+                        self.end_trailer_line()?;
+                        if self.state == State::Done {
+                            if let Some(s) = self.chunker.flush() {
+                                out.push_str(&s);
+                            }
+                        }
+                    } else if self.line.len() > MAX_TRAILER_LINE {
+                        // One extra byte is allowed for the CR of a CRLF
+                        return Err(ChunkedBodyError::TrailerTooLong);
+                    } else {
+                        self.line.push(byte);
+                    }
+                }
+            }
+            data = rest;
+        }
+
+        Ok(non_empty(out))
+    }
+
+    /// Returns `true` once the complete body, including trailers, has been read.
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.state == State::Done
+    }
+
+    /// Returns the trailer fields received after the last chunk.
+    #[inline]
+    pub fn trailers(&self) -> &[(String, String)] {
+        &self.trailers
+    }
+
+    /// Returns any bytes received after the end of the body, such as the start
+    /// of a pipelined response.
+    #[inline]
+    pub fn remainder(&self) -> &[u8] {
+        &self.remainder
+    }
+
+    fn end_size_line(&mut self, size: u64) {
+        self.state = match size {
+            0 => State::Trailer,
+            remaining => State::Data { remaining },
+        };
+    }
+
+    fn end_trailer_line(&mut self) -> Result<(), ChunkedBodyError> {
+        if self.line.last() == Some(&b'\r') {
+            self.line.pop();
+        }
+        if self.line.len() > MAX_TRAILER_LINE {
+            return Err(ChunkedBodyError::TrailerTooLong);
+        }
+        if self.line.is_empty() {
+            self.state = State::Done;
+            return Ok(());
+        }
+
+        let line = String::from_utf8_lossy(&self.line);
+        let (name, value) = line
+            .split_once(':')
+            .ok_or(ChunkedBodyError::InvalidTrailer)?;
+        if name.is_empty() || name.trim() != name {
+            return Err(ChunkedBodyError::InvalidTrailer);
+        }
+        self.trailers
+            .push((String::from(name), String::from(value.trim())));
+        self.line.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_chunks(chunks: &[&[u8]]) -> Result<String, ChunkedBodyError> {
+        let mut d = ChunkedBodyDecoder::new();
+        let mut out = String::new();
+        for chunk in chunks {
+            if let Some(s) = d.push(chunk)? {
+                out.push_str(&s);
+            }
+        }
+        assert!(d.is_done());
+        Ok(out)
+    }
+
+    #[test]
+    fn simple_body() {
+        assert_eq!(
+            decode_chunks(&[b"5\r\nhello\r\n6\r\n world\r\n0\r\n\r\n"]),
+            Ok("hello world".into())
+        );
+    }
+
+    #[test]
+    fn split_at_every_byte() {
+        // '한' (ED 95 9C) and '🦀' (F0 9F A6 80) both straddle HTTP chunks
+        let body: &[u8] =
+            b"2\r\nA\xED\r\n4;ext=\"x\"\r\n\x95\x9C\xF0\x9F\r\n2\r\n\xA6\x80\r\n0\r\n\r\n";
+        let mut d = ChunkedBodyDecoder::new();
+        let mut out = String::new();
+        for b in body {
+            if let Some(s) = d.push(&[*b]).unwrap() {
+                out.push_str(&s);
+            }
+        }
+        assert!(d.is_done());
+        assert_eq!(out, "A한🦀");
+    }
+
+    #[test]
+    fn uppercase_hex_and_bare_lf() {
+        assert_eq!(
+            decode_chunks(&[b"A\n0123456789\n0\n\n"]),
+            Ok("0123456789".into())
+        );
+    }
+
+    #[test]
+    fn trailers_and_remainder() {
+        let mut d = ChunkedBodyDecoder::new();
+        let out = d
+            .push(b"1\r\nx\r\n0\r\nExpires: never\r\nX-Id:  7 \r\n\r\nHTTP/1.1")
+            .unwrap();
+        assert_eq!(out, Some("x".into()));
+        assert_eq!(
+            d.trailers(),
+            [
+                ("Expires".into(), "never".into()),
+                ("X-Id".into(), "7".into())
+            ]
+        );
+        assert_eq!(d.remainder(), b"HTTP/1.1");
+    }
+
+    #[test]
+    fn incomplete_char_flushed_at_end() {
+        assert_eq!(
+            decode_chunks(&[b"2\r\nA\xED\r\n0\r\n\r\n"]),
+            Ok("A\u{FFFD}".into())
+        );
+    }
+
+    #[test]
+    fn malformed_bodies() {
+        let mut d = ChunkedBodyDecoder::new();
+        assert_eq!(d.push(b"zz\r\n"), Err(ChunkedBodyError::InvalidChunkSize));
+
+        let mut d = ChunkedBodyDecoder::new();
+        assert_eq!(d.push(b"1\r\nab\r\n"), Err(ChunkedBodyError::MissingCrlf));
+
+        let mut d = ChunkedBodyDecoder::new();
+        assert_eq!(
+            d.push(b"FFFFFFFFFFFFFFFFF\r\n"),
+            Err(ChunkedBodyError::ChunkSizeOverflow)
+        );
+
+        let mut d = ChunkedBodyDecoder::new();
+        assert_eq!(
+            d.push(b"0\r\nnot a trailer\r\n"),
+            Err(ChunkedBodyError::InvalidTrailer)
+        );
+    }
+
+    #[test]
+    fn trailer_line_length_is_limited() {
+        let value = "v".repeat(MAX_TRAILER_LINE - 3);
+        let mut d = ChunkedBodyDecoder::new();
+        let body = alloc::format!("0\r\nX: {value}\r\n\r\n");
+        assert_eq!(d.push(body.as_bytes()), Ok(None));
+        assert!(d.is_done());
+
+        // A peer that never ends the line is cut off instead of buffered
+        let mut d = ChunkedBodyDecoder::new();
+        assert_eq!(d.push(b"0\r\nX: "), Ok(None));
+        let junk = [b'x'; 1024];
+        let err = (0..16).find_map(|_| d.push(&junk).err());
+        assert_eq!(err, Some(ChunkedBodyError::TrailerTooLong));
+
+        let mut d = ChunkedBodyDecoder::new();
+        let body = alloc::format!("0\r\nX: {value}v\n");
+        assert_eq!(d.push(body.as_bytes()), Err(ChunkedBodyError::TrailerTooLong));
+    }
+}
@@ -0,0 +1,257 @@
+//! Incremental unescaping of JSON string content.
+//!
+//! When JSON is streamed token by token, an escape such as `\n` or a
+//! surrogate pair like `\ud83e\udd80` can be split across chunks just like the
+//! bytes of a multi-byte character. [`JsonUnescaper`] carries both kinds of
+//! partial input between calls.
+
+use alloc::string::String;
+
+use crate::{non_empty, Utf8Chunker};
+
+const REPLACEMENT: char = '\u{FFFD}';
+
+/// Position within an escape sequence.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum State {
+    #[default]
+    Normal,
+    /// Saw `\`.
+    Escape,
+    /// Inside `\uXXXX`, with `digits` hex digits read so far.
+    Unicode { digits: u8, value: u16 },
+}
+
+/// Incremental decoder for the contents of a JSON string literal.
+///
+/// Feed it the raw bytes between the quotes, in chunks split at any byte. It
+/// carries incomplete UTF-8 sequences, partial escapes (`\`, `\u00`) and a
+/// high surrogate waiting for its low half across calls to [`push`](Self::push).
+///
+/// Malformed escapes and unpaired surrogates are replaced with U+FFFD, in the
+/// same lossy spirit as [`Utf8Chunker::flush`].
+///
+/// # Examples
+///
+/// ```
+/// use utf8_chunked::JsonUnescaper;
+///
+/// let mut unescaper = JsonUnescaper::new();
+///
+/// // '🦀' = \ud83e\udd80, split in the middle of the low surrogate
+/// assert_eq!(unescaper.push(br"crab: \ud83e\udd"), Some("crab: ".to_string()));
+/// assert_eq!(unescaper.push(br"80\n"), Some("🦀\n".to_string()));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct JsonUnescaper {
+    chunker: Utf8Chunker,
+    state: State,
+    /// A high surrogate waiting for a following `\uDC00`–`\uDFFF` escape.
+    high_surrogate: Option<u16>,
+}
+
+impl JsonUnescaper {
+    /// Creates a new `JsonUnescaper`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Processes a chunk of raw string content and returns any unescaped text.
+    ///
+    /// Returns `None` if all input is carried as part of an incomplete escape
+    /// or character.
+    pub fn push(&mut self, data: &[u8]) -> Option<String> {
+        let text = self.chunker.push(data)?;
+        let mut out = String::with_capacity(text.len());
+        for c in text.chars() {
+            self.push_char(c, &mut out);
+        }
+        non_empty(out)
+    }
+
+    /// Flushes any carried input at the end of the string.
+    ///
+    /// Incomplete characters, escapes and unpaired high surrogates are replaced
+    /// with U+FFFD. Returns `None` if nothing was carried.
+    pub fn flush(&mut self) -> Option<String> {
+        let mut out = String::new();
+        if let Some(text) = self.chunker.flush() {
+            for c in text.chars() {
+                self.push_char(c, &mut out);
+            }
+        }
+        if self.state != State::Normal || self.high_surrogate.is_some() {
+            out.push(REPLACEMENT);
+        }
+        self.state = State::Normal;
+        self.high_surrogate = None;
+        non_empty(out)
+    }
+
+    /// Returns `true` if no partial character, escape or surrogate is carried.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.chunker.is_empty() && self.state == State::Normal && self.high_surrogate.is_none()
+    }
+
+    fn push_char(&mut self, c: char, out: &mut String) {
+        match self.state {
+            State::Normal => {
+                if c == '\\' {
+                    self.state = State::Escape;
+                } else {
+                    self.take_unpaired(out);
+                    out.push(c);
+                }
+            }
+            State::Escape => {
+                self.state = State::Normal;
+                let unescaped = match c {
+                    'u' => {
+                        self.state = State::Unicode {
+                            digits: 0,
+                            value: 0,
+                        };
+                        return;
+                    }
+                    '"' | '\\' | '/' => c,
+                    'b' => '\u{08}',
+                    'f' => '\u{0C}',
+                    'n' => '\n',
+                    'r' => '\r',
+                    't' => '\t',
+                    _ => REPLACEMENT,
+                };
+                self.take_unpaired(out);
+                out.push(unescaped);
+            }
+            State::Unicode { digits, value } => match c.to_digit(16) {
+                Some(d) => {
+                    let value = (value << 4) | d as u16;
+                    if digits == 3 {
+                        self.state = State::Normal;
+                        self.push_code_unit(value, out);
+                    } else {
+                        self.state = State::Unicode {
+                            digits: digits + 1,
+                            value,
+                        };
+                    }
+                }
+                None => {
+                    // Malformed `\u` escape; replace it and reprocess `c`
+                    self.state = State::Normal;
+                    self.take_unpaired(out);
+                    out.push(REPLACEMENT);
+                    self.push_char(c, out);
+                }
+            },
+        }
+    }
+
+    fn push_code_unit(&mut self, unit: u16, out: &mut String) {
+        match unit {
+            0xD800..=0xDBFF => {
+                self.take_unpaired(out);
+                self.high_surrogate = Some(unit);
+            }
+            0xDC00..=0xDFFF => match self.high_surrogate.take() {
+                Some(high) => {
+                    let c = 0x10000 + (((high as u32) - 0xD800) << 10) + ((unit as u32) - 0xDC00);
+                    out.push(char::from_u32(c).unwrap_or(REPLACEMENT));
+                }
+                None => out.push(REPLACEMENT),
+            },
+            _ => {
+                self.take_unpaired(out);
+                out.push(char::from_u32(unit as u32).unwrap_or(REPLACEMENT));
+            }
+        }
+    }
+
+    /// Replaces a high surrogate that turned out not to be followed by a low one.
+    #[inline]
+    fn take_unpaired(&mut self, out: &mut String) {
+        if self.high_surrogate.take().is_some() {
+            out.push(REPLACEMENT);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unescape_chunks(chunks: &[&[u8]]) -> String {
+        let mut u = JsonUnescaper::new();
+        let mut out = String::new();
+        for chunk in chunks {
+            if let Some(s) = u.push(chunk) {
+                out.push_str(&s);
+            }
+        }
+        if let Some(s) = u.flush() {
+            out.push_str(&s);
+        }
+        out
+    }
+
+    #[test]
+    fn simple_escapes() {
+        assert_eq!(
+            unescape_chunks(&[br#"a\"b\\c\/d\b\f\n\r\t"#]),
+            "a\"b\\c/d\u{08}\u{0C}\n\r\t"
+        );
+    }
+
+    #[test]
+    fn unicode_escape_bmp() {
+        assert_eq!(unescape_chunks(&[br"\uD55C\uae00"]), "한글");
+    }
+
+    #[test]
+    fn surrogate_pair_split_at_every_byte() {
+        let input = br"x\ud83e\udd80y";
+        for split in 1..input.len() {
+            let (a, b) = input.split_at(split);
+            assert_eq!(unescape_chunks(&[a, b]), "x🦀y", "split={split}");
+        }
+    }
+
+    #[test]
+    fn backslash_at_end_of_chunk() {
+        let mut u = JsonUnescaper::new();
+        assert_eq!(u.push(br"ab\"), Some("ab".into()));
+        assert!(!u.is_empty());
+        assert_eq!(u.push(b"n"), Some("\n".into()));
+        assert!(u.is_empty());
+    }
+
+    #[test]
+    fn high_surrogate_carried_across_pushes() {
+        let mut u = JsonUnescaper::new();
+        assert_eq!(u.push(br"\ud83e"), None);
+        assert_eq!(u.push(br"\udd80"), Some("🦀".into()));
+    }
+
+    #[test]
+    fn raw_utf8_split() {
+        // '한' = ED 95 9C
+        assert_eq!(unescape_chunks(&[b"\\t\xED", b"\x95\x9C"]), "\t한");
+    }
+
+    #[test]
+    fn unpaired_surrogates_replaced() {
+        assert_eq!(unescape_chunks(&[br"\ud83ex"]), "\u{FFFD}x");
+        assert_eq!(unescape_chunks(&[br"\udd80"]), "\u{FFFD}");
+        assert_eq!(unescape_chunks(&[br"\ud83e\u0041"]), "\u{FFFD}A");
+        assert_eq!(unescape_chunks(&[br"\ud83e"]), "\u{FFFD}");
+    }
+
+    #[test]
+    fn malformed_escapes_replaced() {
+        assert_eq!(unescape_chunks(&[br"\x"]), "\u{FFFD}");
+        assert_eq!(unescape_chunks(&[br"\u12g"]), "\u{FFFD}g");
+        assert_eq!(unescape_chunks(&[br"\u12"]), "\u{FFFD}");
+    }
+}
@@ -28,13 +28,18 @@
 //! }
 //! ```
 
-#![cfg_attr(not(feature = "tokio"), no_std)]
+#![cfg_attr(not(any(feature = "tokio", feature = "mmap")), no_std)]
 
 extern crate alloc;
 
 use alloc::string::String;
 use alloc::vec::Vec;
 
+#[cfg(not(feature = "simd"))]
+use core::str::from_utf8;
+#[cfg(feature = "simd")]
+use simdutf8::compat::from_utf8;
+
 /// Incremental UTF-8 decoder that buffers incomplete multi-byte sequences.
 ///
 /// `Utf8Chunker` accepts arbitrary byte slices and produces valid UTF-8 strings,
@@ -57,83 +62,138 @@ use alloc::vec::Vec;
 /// assert_eq!(chunker.push(&[0xF0, 0x9F]), None);
 /// assert_eq!(chunker.push(&[0xA6, 0x80]), Some("🦀".to_string()));
 /// ```
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct Utf8Chunker {
     buf: Vec<u8>,
+    /// Still skipping continuation bytes before the first character start.
+    resync: bool,
+    /// Continuation bytes skipped while resyncing.
+    skipped: usize,
 }
 
 impl Utf8Chunker {
     /// Creates a new `Utf8Chunker` with an empty buffer.
     #[inline]
     pub fn new() -> Self {
-        Self { buf: Vec::new() }
+        Self {
+            buf: Vec::new(),
+            resync: false,
+            skipped: 0,
+        }
+    }
+
+    /// Creates a `Utf8Chunker` for a stream that may start in the middle of a
+    /// character, e.g. after seeking to an arbitrary byte offset.
+    ///
+    /// Leading continuation bytes are skipped until the first character start
+    /// instead of being buffered or treated as invalid.
+    /// [`skipped_bytes`](Self::skipped_bytes) reports how many were skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utf8_chunked::Utf8Chunker;
+    ///
+    /// // Seeked into the middle of '한' (ED 95 9C)
+    /// let mut chunker = Utf8Chunker::resync();
+    /// assert_eq!(chunker.push(&[0x95]), None);
+    /// assert_eq!(chunker.push(&[0x9C, b'a']), Some("a".to_string()));
+    /// assert_eq!(chunker.skipped_bytes(), 2);
+    /// ```
+    #[inline]
+    pub fn resync() -> Self {
+        Self {
+            resync: true,
+            ..Self::new()
+        }
     }
 
     /// Processes an incoming byte chunk and returns any complete UTF-8 text.
     ///
     /// Returns `Some(String)` if at least one valid UTF-8 character can be produced,
     /// or `None` if all input bytes are buffered as part of an incomplete sequence.
+    /// Invalid sequences are skipped, and decoding continues after them.
     ///
     /// # Fast Path
     ///
     /// When the internal buffer is empty and `data` is entirely valid UTF-8,
-    /// the data is returned without any copying or allocation.
+    /// `data` is validated once and copied straight into the returned `String`.
+    ///
+    /// # Slow Path
+    ///
+    /// When a partial character is carried over from the previous call, only the
+    /// 1–3 bytes needed to complete it are joined with the buffer and validated.
+    /// The rest of `data` is then validated in place, without being copied into
+    /// the buffer first, and the output is built with a single allocation.
     pub fn push(&mut self, data: &[u8]) -> Option<String> {
         if data.is_empty() {
             return None;
         }
 
-        // Fast path: no pending buffer and data is valid UTF-8
-        if self.buf.is_empty() {
-            if let Ok(s) = core::str::from_utf8(data) {
-                return Some(String::from(s));
-            }
-        }
+        let mut data = data;
+        let mut carried_len = 0;
 
-        // Merge buffer + new data
-        self.buf.extend_from_slice(data);
-
-        // Find how much is valid UTF-8
-        match core::str::from_utf8(&self.buf) {
-            Ok(s) => {
-                let result = String::from(s);
-                self.buf.clear();
-                Some(result)
+        if self.resync {
+            let skip = data.iter().take_while(|&&b| is_continuation(b)).count();
+            self.skipped += skip;
+            if skip == data.len() {
+                return None;
             }
-            Err(e) => {
-                let valid_up_to = e.valid_up_to();
+            self.resync = false;
+            data = &data[skip..];
+        }
 
-                // Check how many trailing bytes form an incomplete sequence
-                let trailing = &self.buf[valid_up_to..];
-                let incomplete_len = incomplete_sequence_len(trailing);
+        if !self.buf.is_empty() {
+            // Complete the carried character with as few bytes as possible
+            let take = self.expected_remaining().min(data.len());
+            self.buf.extend_from_slice(&data[..take]);
 
-                if incomplete_len == 0 && valid_up_to == 0 {
-                    // No valid data and no incomplete sequence — shouldn't normally happen
-                    // with well-formed input, but handle gracefully
+            match from_utf8(&self.buf) {
+                Ok(_) => {
+                    carried_len = self.buf.len();
+                    data = &data[take..];
+                }
+                Err(e) if e.error_len().is_none() => {
+                    // Still a valid prefix, just not complete yet
                     return None;
                 }
-
-                let result = if valid_up_to > 0 {
-                    // Safety: from_utf8 confirmed these bytes are valid
-                    let s = unsafe { core::str::from_utf8_unchecked(&self.buf[..valid_up_to]) };
-                    Some(String::from(s))
-                } else {
-                    None
-                };
-
-                if incomplete_len > 0 {
-                    // Keep incomplete sequence in buffer
-                    let start = self.buf.len() - incomplete_len;
-                    let remaining: Vec<u8> = self.buf[start..].to_vec();
-                    self.buf.clear();
-                    self.buf.extend_from_slice(&remaining);
-                } else {
+                Err(_) => {
+                    // The carried bytes can never form a valid character; drop them
+                    // and decode `data` on its own
                     self.buf.clear();
                 }
+            }
+        }
 
-                result
+        let mut out = String::new();
+        if carried_len > 0 {
+            out.reserve(carried_len + data.len());
+            // Safety: the completed character was validated above
+            out.push_str(unsafe { core::str::from_utf8_unchecked(&self.buf[..carried_len]) });
+        }
+        loop {
+            let (valid_len, error_len) = split_valid(data);
+            if valid_len > 0 {
+                // Sized for the rest of `data`, so valid input allocates once
+                out.reserve(data.len());
+                // Safety: split_valid only reports validated prefixes
+                out.push_str(unsafe { core::str::from_utf8_unchecked(&data[..valid_len]) });
+            }
+            match error_len {
+                // Skip only the invalid sequence and decode the rest
+                Some(len) => data = &data[valid_len + len..],
+                None => {
+                    data = &data[valid_len..];
+                    break;
+                }
             }
         }
+
+        // Keep a trailing incomplete sequence (at most 3 bytes) for the next call
+        self.buf.clear();
+        self.buf.extend_from_slice(data);
+
+        non_empty(out)
     }
 
     /// Flushes any remaining buffered bytes using lossy UTF-8 conversion.
@@ -162,54 +222,65 @@ impl Utf8Chunker {
     pub fn buffered_len(&self) -> usize {
         self.buf.len()
     }
-}
 
-/// Determines how many trailing bytes form an incomplete UTF-8 sequence.
-///
-/// Returns the number of bytes that should be kept in the buffer,
-/// or 0 if the trailing bytes are not a valid incomplete sequence.
-fn incomplete_sequence_len(trailing: &[u8]) -> usize {
-    if trailing.is_empty() {
-        return 0;
-    }
-
-    // Walk backwards from the end to find the start of an incomplete sequence.
-    // We look for a leading byte (not a continuation byte) within the last 3 bytes.
-    let len = trailing.len();
-    let check_len = len.min(4);
-
-    for i in (0..check_len).rev() {
-        let idx = len - 1 - i;
-        let byte = trailing[idx];
-
-        if byte & 0x80 == 0 {
-            // ASCII byte — cannot be start of incomplete multibyte sequence
-            // Everything before this is either complete or invalid
-            if i == 0 {
-                return 0; // trailing ends with ASCII, nothing incomplete
-            }
-            continue;
-        }
+    /// Returns the bytes currently buffered as an incomplete sequence.
+    #[inline]
+    pub fn pending_bytes(&self) -> &[u8] {
+        &self.buf
+    }
 
-        if byte & 0xC0 != 0x80 {
-            // This is a leading byte (110xxxxx, 1110xxxx, or 11110xxx)
-            let expected_len = utf8_char_len(byte);
-            let available = i + 1; // bytes from this position to end
-
-            if expected_len > 0 && available < expected_len {
-                // Incomplete sequence — keep these bytes
-                return available;
-            } else {
-                // Complete or invalid sequence
-                return 0;
-            }
+    /// Returns how many more bytes are needed to complete the pending character.
+    ///
+    /// Returns 0 if the buffer is empty, or if the buffered bytes do not start
+    /// with a valid leading byte (e.g. stray continuation bytes).
+    #[inline]
+    pub fn expected_remaining(&self) -> usize {
+        match self.buf.first() {
+            Some(&lead) => utf8_char_len(lead).saturating_sub(self.buf.len()),
+            None => 0,
         }
-        // Continuation byte (10xxxxxx) — keep looking for leading byte
     }
 
-    // All checked bytes are continuation bytes — this is malformed,
-    // but keep them in case more data arrives
-    check_len.min(3)
+    /// Returns the number of leading continuation bytes skipped by a chunker
+    /// created with [`resync`](Self::resync).
+    #[inline]
+    pub fn skipped_bytes(&self) -> usize {
+        self.skipped
+    }
+
+    /// Returns `true` if a [`resync`](Self::resync) chunker has not yet seen
+    /// the start of a character.
+    #[inline]
+    pub fn is_resyncing(&self) -> bool {
+        self.resync
+    }
+
+    /// Discards any buffered bytes, returning the chunker to its initial state.
+    ///
+    /// The skipped byte count is cleared as well. A chunker created with
+    /// [`resync`](Self::resync) does not go back into resync mode, since a
+    /// reset usually means the next byte starts a new stream; assign
+    /// `Utf8Chunker::resync()` instead when resuming mid-character.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.buf.clear();
+        self.resync = false;
+        self.skipped = 0;
+    }
+}
+
+/// Splits `data` at its first invalid or incomplete sequence.
+///
+/// Returns `(valid_len, error_len)`: `data[..valid_len]` is valid UTF-8 and the
+/// next `error_len` bytes are an invalid sequence to skip. `error_len` is
+/// `None` if everything after the valid prefix (possibly nothing) is the start
+/// of an incomplete character to carry into the next chunk.
+#[inline]
+pub(crate) fn split_valid(data: &[u8]) -> (usize, Option<usize>) {
+    match from_utf8(data) {
+        Ok(_) => (data.len(), None),
+        Err(e) => (e.valid_up_to(), e.error_len()),
+    }
 }
 
 /// Returns the expected length of a UTF-8 character from its leading byte.
@@ -229,14 +300,80 @@ fn utf8_char_len(byte: u8) -> usize {
     }
 }
 
+/// Returns `true` for a UTF-8 continuation byte (`10xxxxxx`).
+#[inline]
+pub(crate) fn is_continuation(byte: u8) -> bool {
+    byte & 0xC0 == 0x80
+}
+
+/// Returns `Some(s)`, or `None` if `s` is empty.
+#[inline]
+pub(crate) fn non_empty(s: String) -> Option<String> {
+    if s.is_empty() {
+        None
+    } else {
+        Some(s)
+    }
+}
+
+// ============================================================
+// Decoders layered on Utf8Chunker
+// ============================================================
+
+mod ansi;
+mod http_chunked;
+mod json_unescape;
+mod line_ending;
+#[cfg(feature = "mmap")]
+mod mmap;
+mod ndjson;
+#[cfg(feature = "normalization")]
+mod normalize;
+mod parallel;
+mod reverse;
+mod sanitize;
+mod scrollback;
+mod split;
+mod sse;
+#[cfg(feature = "width")]
+mod width;
+mod ws;
+
+pub use ansi::{
+    AnsiChunker, AnsiColor, AnsiMode, AnsiSpan, AnsiStyle, AnsiStyler, MAX_PENDING_ESCAPE,
+};
+pub use http_chunked::{ChunkedBodyDecoder, ChunkedBodyError};
+pub use json_unescape::JsonUnescaper;
+pub use line_ending::{LineEnding, LineEndingNormalizer};
+#[cfg(feature = "mmap")]
+pub use mmap::MmapTextChunks;
+#[cfg(feature = "json")]
+pub use ndjson::NdjsonError;
+pub use ndjson::{NdjsonDecoder, NdjsonRecord};
+#[cfg(feature = "normalization")]
+pub use normalize::{NormalizationForm, UnicodeNormalizer};
+#[cfg(feature = "rayon")]
+pub use parallel::par_decode;
+pub use parallel::{chunk_ranges, ParDecodeError};
+pub use reverse::ReverseUtf8Chunker;
+pub use sanitize::{SanitizeMode, TerminalSanitizer};
+pub use scrollback::TextScrollback;
+pub use split::{PieceStats, SplitUnit, Utf8Split, Utf8Splitter};
+pub use sse::{SseDecoder, SseEvent};
+#[cfg(feature = "width")]
+pub use width::{WidthChunker, WidthPiece};
+pub use ws::{WsTextAssembler, WsTextError};
+
 // ============================================================
 // tokio feature: async utilities
 // ============================================================
 
 #[cfg(feature = "tokio")]
 mod async_support {
-    use super::Utf8Chunker;
-    use tokio_util::bytes::BytesMut;
+    use super::{from_utf8, split_valid, Utf8Chunker};
+    use core::fmt;
+    use core::ops::Deref;
+    use tokio_util::bytes::{Buf, Bytes, BytesMut};
     use std::io;
     use tokio::io::AsyncRead;
     use tokio_stream::Stream;
@@ -294,6 +431,205 @@ mod async_support {
         }
     }
 
+    /// An immutable, reference-counted byte buffer that is guaranteed to be valid UTF-8.
+    ///
+    /// `Utf8Bytes` wraps a [`Bytes`] and derefs to `str`. Cloning it only bumps a
+    /// reference count, so the same text can be handed to many consumers cheaply.
+    ///
+    /// ```
+    /// use utf8_chunked::Utf8Bytes;
+    ///
+    /// let text = Utf8Bytes::from_static("한글");
+    /// let copy = text.clone(); // no copy of the text
+    /// assert_eq!(&*copy, "한글");
+    /// ```
+    #[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub struct Utf8Bytes(Bytes);
+
+    impl Utf8Bytes {
+        /// Creates a `Utf8Bytes` from a static string without copying.
+        #[inline]
+        pub const fn from_static(s: &'static str) -> Self {
+            Self(Bytes::from_static(s.as_bytes()))
+        }
+
+        /// Converts `bytes` into `Utf8Bytes`, failing if they are not valid UTF-8.
+        pub fn try_from_bytes(bytes: Bytes) -> Result<Self, core::str::Utf8Error> {
+            match from_utf8(&bytes) {
+                Ok(_) => Ok(Self(bytes)),
+                // Validate again only to build the standard error type
+                Err(_) => Err(core::str::from_utf8(&bytes).unwrap_err()),
+            }
+        }
+
+        /// Returns the text as a string slice.
+        #[inline]
+        pub fn as_str(&self) -> &str {
+            // Safety: the contents are validated on construction
+            unsafe { core::str::from_utf8_unchecked(&self.0) }
+        }
+
+        /// Returns the underlying `Bytes`.
+        #[inline]
+        pub fn into_bytes(self) -> Bytes {
+            self.0
+        }
+    }
+
+    impl Deref for Utf8Bytes {
+        type Target = str;
+
+        #[inline]
+        fn deref(&self) -> &str {
+            self.as_str()
+        }
+    }
+
+    impl AsRef<str> for Utf8Bytes {
+        #[inline]
+        fn as_ref(&self) -> &str {
+            self.as_str()
+        }
+    }
+
+    impl AsRef<[u8]> for Utf8Bytes {
+        #[inline]
+        fn as_ref(&self) -> &[u8] {
+            &self.0
+        }
+    }
+
+    impl fmt::Debug for Utf8Bytes {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt::Debug::fmt(self.as_str(), f)
+        }
+    }
+
+    impl fmt::Display for Utf8Bytes {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt::Display::fmt(self.as_str(), f)
+        }
+    }
+
+    impl From<String> for Utf8Bytes {
+        #[inline]
+        fn from(s: String) -> Self {
+            Self(Bytes::from(s))
+        }
+    }
+
+    impl From<&'static str> for Utf8Bytes {
+        #[inline]
+        fn from(s: &'static str) -> Self {
+            Self::from_static(s)
+        }
+    }
+
+    impl From<Utf8Bytes> for Bytes {
+        #[inline]
+        fn from(s: Utf8Bytes) -> Self {
+            s.0
+        }
+    }
+
+    impl PartialEq<str> for Utf8Bytes {
+        fn eq(&self, other: &str) -> bool {
+            self.as_str() == other
+        }
+    }
+
+    impl PartialEq<&str> for Utf8Bytes {
+        fn eq(&self, other: &&str) -> bool {
+            self.as_str() == *other
+        }
+    }
+
+    /// A `tokio_util::codec::Decoder` that produces [`Utf8Bytes`] without copying the text.
+    ///
+    /// Valid UTF-8 is split off the read buffer and frozen in place. The only bytes
+    /// ever copied are those of a character split across two reads, which is
+    /// emitted as its own small item once completed.
+    ///
+    /// ```
+    /// use utf8_chunked::Utf8BytesCodec;
+    /// use tokio_util::codec::FramedRead;
+    /// use tokio_stream::StreamExt;
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() -> std::io::Result<()> {
+    /// let reader: &[u8] = "한글 text".as_bytes();
+    /// let mut framed = FramedRead::new(reader, Utf8BytesCodec::new());
+    /// let mut text = String::new();
+    /// while let Some(piece) = framed.next().await {
+    ///     text.push_str(&piece?);
+    /// }
+    /// assert_eq!(text, "한글 text");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[derive(Debug, Default)]
+    pub struct Utf8BytesCodec {
+        chunker: Utf8Chunker,
+    }
+
+    impl Utf8BytesCodec {
+        /// Creates a new `Utf8BytesCodec`.
+        pub fn new() -> Self {
+            Self {
+                chunker: Utf8Chunker::new(),
+            }
+        }
+    }
+
+    impl Decoder for Utf8BytesCodec {
+        type Item = Utf8Bytes;
+        type Error = io::Error;
+
+        fn decode(&mut self, buf: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+            if buf.is_empty() {
+                return Ok(None);
+            }
+
+            // Complete a character carried over from the previous read. Stray
+            // continuation bytes report nothing remaining, so always take at least one.
+            while !self.chunker.is_empty() {
+                let take = self.chunker.expected_remaining().max(1).min(buf.len());
+                let head = buf.split_to(take);
+                if let Some(s) = self.chunker.push(&head) {
+                    return Ok(Some(Utf8Bytes::from(s)));
+                }
+                if buf.is_empty() {
+                    return Ok(None);
+                }
+            }
+
+            loop {
+                let (valid_len, error_len) = split_valid(buf);
+                if valid_len > 0 {
+                    // Anything after the valid prefix is left for the next call
+                    return Ok(Some(Utf8Bytes(buf.split_to(valid_len).freeze())));
+                }
+                match error_len {
+                    // Skip the invalid sequence, as `Utf8Chunker` does
+                    Some(len) => buf.advance(len),
+                    None => {
+                        // Carry the incomplete tail
+                        let tail = buf.split();
+                        self.chunker.push(&tail);
+                        return Ok(None);
+                    }
+                }
+            }
+        }
+
+        fn decode_eof(&mut self, buf: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+            if let Some(text) = self.decode(buf)? {
+                return Ok(Some(text));
+            }
+            Ok(self.chunker.flush().map(Utf8Bytes::from))
+        }
+    }
+
     /// Creates an async stream of UTF-8 strings from an `AsyncRead` source.
     ///
     /// Multi-byte characters split across read boundaries are automatically
@@ -319,7 +655,25 @@ mod async_support {
 }
 
 #[cfg(feature = "tokio")]
-pub use async_support::{utf8_safe_stream, Utf8Codec};
+pub use async_support::{utf8_safe_stream, Utf8Bytes, Utf8BytesCodec, Utf8Codec};
+
+#[cfg(feature = "tokio")]
+mod broadcast;
+#[cfg(feature = "tokio")]
+mod follow;
+#[cfg(feature = "tokio")]
+mod process;
+#[cfg(all(feature = "pty", target_os = "linux"))]
+mod pty;
+
+#[cfg(feature = "tokio")]
+pub use broadcast::{TextBroadcaster, TextEvent, TextSubscriber};
+#[cfg(feature = "tokio")]
+pub use follow::FileFollower;
+#[cfg(feature = "tokio")]
+pub use process::{child_output, spawn_output, ChildEvent, StreamKind};
+#[cfg(all(feature = "pty", target_os = "linux"))]
+pub use pty::{spawn_pty, PtyOptions, PtyProcess, PtySize, PtyWriter};
 
 // ============================================================
 // Unit tests
@@ -328,6 +682,7 @@ pub use async_support::{utf8_safe_stream, Utf8Codec};
 #[cfg(test)]
 mod tests {
     use super::*;
+    use alloc::{format, vec};
 
     #[test]
     fn ascii_passthrough() {
@@ -422,6 +777,121 @@ mod tests {
         assert_eq!(c.push(text.as_bytes()), Some(text.into()));
     }
 
+    #[test]
+    fn carry_completed_before_large_chunk() {
+        let mut c = Utf8Chunker::new();
+        let tail = "가".repeat(1000);
+        assert_eq!(c.push(&[0xED, 0x95]), None);
+        let mut data = vec![0x9C];
+        data.extend_from_slice(tail.as_bytes());
+        assert_eq!(c.push(&data), Some(format!("한{tail}")));
+        assert!(c.is_empty());
+    }
+
+    #[test]
+    fn invalid_carry_is_dropped() {
+        let mut c = Utf8Chunker::new();
+        // Leading byte of '한' followed by ASCII instead of continuation bytes
+        assert_eq!(c.push(&[0xED]), None);
+        assert_eq!(c.push(b"abc"), Some("abc".into()));
+        assert!(c.is_empty());
+    }
+
+    #[test]
+    fn buffer_never_exceeds_three_bytes() {
+        let mut c = Utf8Chunker::new();
+        assert_eq!(c.push(b"\xFFabc"), Some("abc".into()));
+        assert!(c.buffered_len() <= 3);
+        assert_eq!(c.push(b"def"), Some("def".into()));
+        assert!(c.is_empty());
+    }
+
+    #[test]
+    fn invalid_bytes_mid_chunk_are_skipped() {
+        let mut c = Utf8Chunker::new();
+        assert_eq!(c.push(b"hi\xFFthere"), Some("hithere".into()));
+        // Stray continuation bytes and an invalid sequence before a split '한'
+        assert_eq!(c.push(b"a\x80\x80b\xED\xA0\x80c\xED"), Some("abc".into()));
+        assert_eq!(c.pending_bytes(), &[0xED]);
+        assert_eq!(c.push(&[0x95, 0x9C]), Some("한".into()));
+    }
+
+    #[test]
+    fn pending_bytes_and_expected_remaining() {
+        let mut c = Utf8Chunker::new();
+        assert_eq!(c.pending_bytes(), b"");
+        assert_eq!(c.expected_remaining(), 0);
+
+        // '🦀' = F0 9F A6 80
+        c.push(&[0xF0]);
+        assert_eq!(c.pending_bytes(), &[0xF0]);
+        assert_eq!(c.expected_remaining(), 3);
+        c.push(&[0x9F, 0xA6]);
+        assert_eq!(c.pending_bytes(), &[0xF0, 0x9F, 0xA6]);
+        assert_eq!(c.expected_remaining(), 1);
+        assert_eq!(c.push(&[0x80]), Some("🦀".into()));
+        assert_eq!(c.expected_remaining(), 0);
+    }
+
+    #[test]
+    fn clone_forks_state() {
+        let mut a = Utf8Chunker::new();
+        a.push(&[0xED, 0x95]);
+        let mut b = a.clone();
+        assert_eq!(a, b);
+
+        assert_eq!(a.push(&[0x9C]), Some("한".into()));
+        assert_ne!(a, b);
+        assert_eq!(b.push(&[0x9C, b'!']), Some("한!".into()));
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn reset_discards_pending() {
+        let mut c = Utf8Chunker::new();
+        c.push(&[0xE4, 0xB8]);
+        c.reset();
+        assert!(c.is_empty());
+        assert_eq!(c, Utf8Chunker::new());
+        assert_eq!(c.flush(), None);
+    }
+
+    #[test]
+    fn resync_skips_leading_continuation_bytes() {
+        // Seeked to offset 2 of "🦀한": A6 80 | ED 95 9C
+        let mut c = Utf8Chunker::resync();
+        assert!(c.is_resyncing());
+        assert_eq!(c.push(&[0xA6, 0x80, 0xED]), None);
+        assert!(!c.is_resyncing());
+        assert_eq!(c.push(&[0x95, 0x9C]), Some("한".into()));
+        assert_eq!(c.skipped_bytes(), 2);
+    }
+
+    #[test]
+    fn resync_only_skips_at_start() {
+        let mut c = Utf8Chunker::resync();
+        assert_eq!(c.push(b"a"), Some("a".into()));
+        // A stray continuation byte later on is an invalid sequence as usual
+        assert_eq!(c.push(&[0x80, b'b']), Some("b".into()));
+        assert_eq!(c.skipped_bytes(), 0);
+        assert_eq!(c.buffered_len(), 0);
+    }
+
+    #[test]
+    fn reset_leaves_resync_mode() {
+        let mut c = Utf8Chunker::resync();
+        assert_eq!(c.push(&[0x80, b'a', 0xED]), Some("a".into()));
+        assert_eq!(c.skipped_bytes(), 1);
+        c.reset();
+        assert_eq!(c, Utf8Chunker::new());
+        assert!(!c.is_resyncing());
+        assert_eq!(c.skipped_bytes(), 0);
+
+        let mut c = Utf8Chunker::resync();
+        c.reset();
+        assert_eq!(c, Utf8Chunker::new());
+    }
+
     #[test]
     fn default_trait() {
         let c = Utf8Chunker::default();
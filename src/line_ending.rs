@@ -0,0 +1,154 @@
+//! Line-ending normalization for decoded text streams.
+
+use alloc::string::String;
+
+use crate::non_empty;
+
+/// The line ending produced by a [`LineEndingNormalizer`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    /// `\n`
+    #[default]
+    Lf,
+    /// `\r\n`
+    CrLf,
+}
+
+impl LineEnding {
+    /// Returns the line ending as a string.
+    #[inline]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
+    }
+}
+
+/// Converts `\r\n`, lone `\r` and lone `\n` to a single line ending.
+///
+/// This is a transform stage for text that has already been decoded, e.g. the
+/// output of [`Utf8Chunker::push`](crate::Utf8Chunker::push). A `\r` at the end
+/// of one push and a `\n` at the start of the next are treated as one `\r\n`.
+///
+/// A trailing `\r` is converted immediately rather than held back, so there is
+/// nothing to flush at the end of the stream.
+///
+/// # Examples
+///
+/// ```
+/// use utf8_chunked::{LineEnding, LineEndingNormalizer};
+///
+/// let mut normalizer = LineEndingNormalizer::new(LineEnding::Lf);
+///
+/// // Windows output split between '\r' and '\n'
+/// assert_eq!(normalizer.push("line 1\r"), Some("line 1\n".to_string()));
+/// assert_eq!(normalizer.push("\nline 2\r\n"), Some("line 2\n".to_string()));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct LineEndingNormalizer {
+    target: LineEnding,
+    /// The previous push ended with `\r`, so a leading `\n` completes it.
+    skip_lf: bool,
+}
+
+impl LineEndingNormalizer {
+    /// Creates a new `LineEndingNormalizer` producing `target` line endings.
+    pub fn new(target: LineEnding) -> Self {
+        Self {
+            target,
+            skip_lf: false,
+        }
+    }
+
+    /// Normalizes the line endings in `text`.
+    ///
+    /// Returns `None` if nothing is left, e.g. when `text` is only the `\n` of
+    /// a `\r\n` whose `\r` was already converted.
+    pub fn push(&mut self, text: &str) -> Option<String> {
+        let mut rest = text;
+        if self.skip_lf && !rest.is_empty() {
+            self.skip_lf = false;
+            rest = rest.strip_prefix('\n').unwrap_or(rest);
+        }
+
+        let newline = self.target.as_str();
+        let mut out = String::with_capacity(rest.len());
+
+        while let Some(pos) = rest.find(['\r', '\n']) {
+            out.push_str(&rest[..pos]);
+            out.push_str(newline);
+
+            let is_cr = rest.as_bytes()[pos] == b'\r';
+            rest = &rest[pos + 1..];
+            if is_cr {
+                if rest.is_empty() {
+                    self.skip_lf = true;
+                } else {
+                    rest = rest.strip_prefix('\n').unwrap_or(rest);
+                }
+            }
+        }
+        out.push_str(rest);
+
+        non_empty(out)
+    }
+
+    /// Forgets a trailing `\r` from the previous push.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.skip_lf = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    fn normalize(target: LineEnding, pieces: &[&str]) -> String {
+        let mut n = LineEndingNormalizer::new(target);
+        pieces
+            .iter()
+            .filter_map(|p| n.push(p))
+            .collect::<Vec<_>>()
+            .concat()
+    }
+
+    #[test]
+    fn to_lf() {
+        assert_eq!(normalize(LineEnding::Lf, &["a\r\nb\rc\nd"]), "a\nb\nc\nd");
+    }
+
+    #[test]
+    fn to_crlf() {
+        assert_eq!(
+            normalize(LineEnding::CrLf, &["a\r\nb\rc\nd"]),
+            "a\r\nb\r\nc\r\nd"
+        );
+    }
+
+    #[test]
+    fn crlf_split_between_pushes() {
+        assert_eq!(normalize(LineEnding::Lf, &["a\r", "\nb"]), "a\nb");
+        assert_eq!(normalize(LineEnding::CrLf, &["a\r", "\n", "b"]), "a\r\nb");
+    }
+
+    #[test]
+    fn blank_lines_preserved_across_splits() {
+        assert_eq!(
+            normalize(LineEnding::Lf, &["a\r", "\n\r", "\n\r", "\nb"]),
+            "a\n\n\nb"
+        );
+        assert_eq!(normalize(LineEnding::Lf, &["a\r", "\r", "\nb"]), "a\n\nb");
+    }
+
+    #[test]
+    fn lf_only_push_after_cr_is_none() {
+        let mut n = LineEndingNormalizer::new(LineEnding::Lf);
+        assert_eq!(n.push("x\r"), Some("x\n".into()));
+        assert_eq!(n.push(""), None);
+        assert_eq!(n.push("\n"), None);
+        assert_eq!(n.push("\n"), Some("\n".into()));
+    }
+}
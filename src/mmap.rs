@@ -0,0 +1,178 @@
+//! Zero-copy text iteration over memory-mapped files (requires the `mmap`
+//! feature).
+
+use memmap2::Mmap;
+
+use crate::{from_utf8, is_continuation};
+
+/// Iterator over a memory-mapped file in windows aligned to UTF-8 character
+/// boundaries, yielding `&str` slices that borrow directly from the map.
+///
+/// Each window is at most `window` bytes (or one character, if `window` is
+/// smaller than a character). Invalid bytes follow the same policy as
+/// [`Utf8Chunker`](crate::Utf8Chunker): an invalid sequence in the middle of
+/// the file is dropped, and an incomplete character at the very end is
+/// yielded as U+FFFD, like [`Utf8Chunker::flush`](crate::Utf8Chunker::flush).
+/// A window is cut short before an invalid sequence, so the iterator never
+/// copies.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::fs::File;
+/// use memmap2::Mmap;
+/// use utf8_chunked::MmapTextChunks;
+///
+/// let file = File::open("app.log")?;
+/// // Safety: the file must not be truncated or modified while it is mapped
+/// let map = unsafe { Mmap::map(&file)? };
+/// for text in MmapTextChunks::new(&map, 64 * 1024) {
+///     print!("{text}");
+/// }
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct MmapTextChunks<'a> {
+    data: &'a [u8],
+    window: usize,
+    pos: usize,
+}
+
+impl<'a> MmapTextChunks<'a> {
+    /// Creates an iterator over `map` in windows of at most `window` bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window` is 0.
+    pub fn new(map: &'a Mmap, window: usize) -> Self {
+        assert!(window > 0, "window must be at least 1");
+        Self {
+            data: map,
+            window,
+            pos: 0,
+        }
+    }
+
+    /// Returns the byte offset in the file of the next window.
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns the end of the window starting at `self.pos`, moved back to a
+    /// character start where possible.
+    fn window_end(&self) -> usize {
+        let end = (self.pos + self.window).min(self.data.len());
+        if end == self.data.len() {
+            return end;
+        }
+        let mut aligned = end;
+        while aligned > self.pos && end - aligned < 3 && is_continuation(self.data[aligned]) {
+            aligned -= 1;
+        }
+        if aligned > self.pos {
+            // A run of more than 3 continuation bytes is invalid anyway
+            return if is_continuation(self.data[aligned]) {
+                end
+            } else {
+                aligned
+            };
+        }
+        // The window is smaller than the character at `pos`: take it whole
+        let mut end = self.pos + 1;
+        while end < self.data.len() && end - self.pos < 4 && is_continuation(self.data[end]) {
+            end += 1;
+        }
+        end
+    }
+}
+
+impl<'a> Iterator for MmapTextChunks<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        while self.pos < self.data.len() {
+            let end = self.window_end();
+            let window = &self.data[self.pos..end];
+            let e = match from_utf8(window) {
+                Ok(text) => {
+                    self.pos = end;
+                    return Some(text);
+                }
+                Err(e) => e,
+            };
+
+            let valid_up_to = e.valid_up_to();
+            if valid_up_to > 0 {
+                // Safety: from_utf8 validated this prefix
+                let text = unsafe { core::str::from_utf8_unchecked(&window[..valid_up_to]) };
+                self.pos += valid_up_to;
+                return Some(text);
+            }
+
+            // The window starts with an invalid or incomplete sequence; measure
+            // it against the rest of the file rather than the window
+            let rest = &self.data[self.pos..(self.pos + 4).min(self.data.len())];
+            match from_utf8(rest).err().and_then(|e| e.error_len()) {
+                Some(len) => self.pos += len,
+                None => {
+                    // Truncated character at the end of the file
+                    self.pos = self.data.len();
+                    return Some("\u{FFFD}");
+                }
+            }
+        }
+        None
+    }
+}
+
+impl core::iter::FusedIterator for MmapTextChunks<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::string::String;
+    use std::vec::Vec;
+
+    fn map(bytes: &[u8]) -> Mmap {
+        let mut file = tempfile::tempfile().unwrap();
+        file.write_all(bytes).unwrap();
+        unsafe { Mmap::map(&file).unwrap() }
+    }
+
+    #[test]
+    fn windows_are_aligned_and_borrowed() {
+        let text = "log 한국어 🦀 é\n".repeat(20);
+        let map = map(text.as_bytes());
+        for window in 1..=16 {
+            let pieces: Vec<&str> = MmapTextChunks::new(&map, window).collect();
+            assert_eq!(pieces.concat(), text, "window={window}");
+            for piece in &pieces {
+                assert!(piece.len() <= window.max(4));
+                let start = piece.as_ptr() as usize - map.as_ptr() as usize;
+                assert!(start < map.len());
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_bytes_follow_chunker_policy() {
+        // Invalid byte mid-file is dropped; truncated '한' at EOF becomes U+FFFD
+        let map = map(b"ab\xFFcd\xED\x95");
+        for window in 1..=8 {
+            let out: String = MmapTextChunks::new(&map, window).collect();
+            assert_eq!(out, "abcd\u{FFFD}", "window={window}");
+        }
+    }
+
+    #[test]
+    fn offset_tracks_progress() {
+        let map = map("가나다".as_bytes());
+        let mut chunks = MmapTextChunks::new(&map, 4);
+        assert_eq!(chunks.next(), Some("가"));
+        assert_eq!(chunks.offset(), 3);
+        assert_eq!(chunks.by_ref().count(), 2);
+        assert_eq!(chunks.next(), None);
+    }
+}
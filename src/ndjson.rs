@@ -0,0 +1,254 @@
+//! Newline-delimited JSON (NDJSON) framing on top of [`Utf8Chunker`].
+//!
+//! Each non-blank line is one JSON document. Lines may end with `\n` or
+//! `\r\n`, and records may be split across chunks at any byte.
+
+use alloc::collections::VecDeque;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::mem;
+
+use crate::Utf8Chunker;
+
+/// One NDJSON record: the text of a single JSON document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NdjsonRecord {
+    /// The 1-based line number the record appeared on.
+    pub line: usize,
+    /// The record text, without the line terminator.
+    pub text: String,
+}
+
+/// Incremental NDJSON decoder.
+///
+/// Yields one [`NdjsonRecord`] per non-blank line. Blank lines are skipped but
+/// still counted, so [`NdjsonRecord::line`] always matches the input.
+///
+/// # Examples
+///
+/// ```
+/// use utf8_chunked::NdjsonDecoder;
+///
+/// let mut decoder = NdjsonDecoder::new();
+///
+/// // '한' = ED 95 9C, split across two chunks
+/// let records = decoder.push(b"{\"a\":1}\r\n\n{\"name\":\"\xED\x95");
+/// assert_eq!(records.len(), 1);
+/// assert_eq!(records[0].text, r#"{"a":1}"#);
+///
+/// let records = decoder.push(b"\x9C\"}\n");
+/// assert_eq!(records[0].line, 3);
+/// assert_eq!(records[0].text, r#"{"name":"한"}"#);
+/// ```
+#[derive(Debug, Default)]
+pub struct NdjsonDecoder {
+    chunker: Utf8Chunker,
+    /// Text of the line currently being received.
+    line: String,
+    /// Number of lines completed so far.
+    line_no: usize,
+    records: VecDeque<NdjsonRecord>,
+}
+
+impl NdjsonDecoder {
+    /// Creates a new `NdjsonDecoder`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Processes an incoming byte chunk and returns all records it completed.
+    pub fn push(&mut self, data: &[u8]) -> Vec<NdjsonRecord> {
+        self.feed(data);
+        self.records.drain(..).collect()
+    }
+
+    /// Returns the final record if the stream did not end with a newline.
+    ///
+    /// Call this when the byte stream is finished. An incomplete multi-byte
+    /// sequence is replaced with U+FFFD, as [`Utf8Chunker::flush`] does.
+    pub fn finish(&mut self) -> Option<NdjsonRecord> {
+        if let Some(text) = self.chunker.flush() {
+            self.line.push_str(&text);
+        }
+        if self.line.is_empty() {
+            return None;
+        }
+        let line = mem::take(&mut self.line);
+        self.line_no += 1;
+        self.record(line)
+    }
+
+    fn feed(&mut self, data: &[u8]) {
+        let Some(text) = self.chunker.push(data) else {
+            return;
+        };
+
+        let mut rest = text.as_str();
+        while let Some(pos) = rest.find('\n') {
+            self.line.push_str(&rest[..pos]);
+            rest = &rest[pos + 1..];
+
+            let line = mem::take(&mut self.line);
+            self.line_no += 1;
+            if let Some(record) = self.record(line) {
+                self.records.push_back(record);
+            }
+        }
+        self.line.push_str(rest);
+    }
+
+    fn record(&self, mut text: String) -> Option<NdjsonRecord> {
+        if text.ends_with('\r') {
+            text.pop();
+        }
+        if text.trim().is_empty() {
+            return None;
+        }
+        Some(NdjsonRecord {
+            line: self.line_no,
+            text,
+        })
+    }
+}
+
+#[cfg(feature = "json")]
+mod json {
+    use super::NdjsonRecord;
+    use core::fmt;
+    use serde::de::DeserializeOwned;
+
+    /// A malformed NDJSON record.
+    #[derive(Debug)]
+    pub struct NdjsonError {
+        /// The 1-based line number of the malformed record.
+        pub line: usize,
+        /// The underlying parse error.
+        pub error: serde_json::Error,
+    }
+
+    impl fmt::Display for NdjsonError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "line {}: {}", self.line, self.error)
+        }
+    }
+
+    impl core::error::Error for NdjsonError {
+        fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+            Some(&self.error)
+        }
+    }
+
+    impl NdjsonRecord {
+        /// Parses the record with `serde_json`.
+        ///
+        /// On failure the error carries the record's line number.
+        pub fn parse<T: DeserializeOwned>(&self) -> Result<T, NdjsonError> {
+            serde_json::from_str(&self.text).map_err(|error| NdjsonError {
+                line: self.line,
+                error,
+            })
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+pub use json::NdjsonError;
+
+#[cfg(feature = "tokio")]
+mod codec {
+    use super::{NdjsonDecoder, NdjsonRecord};
+    use std::io;
+    use tokio_util::bytes::BytesMut;
+    use tokio_util::codec::Decoder;
+
+    /// Decodes a byte stream into [`NdjsonRecord`]s.
+    ///
+    /// A final record without a trailing newline is yielded at end of stream.
+    impl Decoder for NdjsonDecoder {
+        type Item = NdjsonRecord;
+        type Error = io::Error;
+
+        fn decode(&mut self, buf: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+            if self.records.is_empty() && !buf.is_empty() {
+                let data = buf.split_to(buf.len());
+                self.feed(&data);
+            }
+            Ok(self.records.pop_front())
+        }
+
+        fn decode_eof(&mut self, buf: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+            if let Some(record) = self.decode(buf)? {
+                return Ok(Some(record));
+            }
+            Ok(self.finish())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(records: &[NdjsonRecord]) -> Vec<&str> {
+        records.iter().map(|r| r.text.as_str()).collect()
+    }
+
+    #[test]
+    fn lines_and_crlf() {
+        let mut d = NdjsonDecoder::new();
+        let records = d.push(b"{\"a\":1}\n{\"b\":2}\r\n");
+        assert_eq!(texts(&records), [r#"{"a":1}"#, r#"{"b":2}"#]);
+        assert_eq!(records[1].line, 2);
+    }
+
+    #[test]
+    fn blank_lines_skipped_but_counted() {
+        let mut d = NdjsonDecoder::new();
+        let records = d.push(b"\n  \r\n1\n\n2\n");
+        assert_eq!(texts(&records), ["1", "2"]);
+        assert_eq!(records[0].line, 3);
+        assert_eq!(records[1].line, 5);
+    }
+
+    #[test]
+    fn crlf_split_between_chunks() {
+        let mut d = NdjsonDecoder::new();
+        assert!(d.push(b"[1]\r").is_empty());
+        let records = d.push(b"\n[2]\n");
+        assert_eq!(texts(&records), ["[1]", "[2]"]);
+    }
+
+    #[test]
+    fn record_split_at_every_byte() {
+        let input = "{\"msg\":\"안녕 🦀\"}\n{\"msg\":\"世界\"}\n".as_bytes();
+        let mut d = NdjsonDecoder::new();
+        let records: Vec<NdjsonRecord> = input.iter().flat_map(|&b| d.push(&[b])).collect();
+        assert_eq!(
+            texts(&records),
+            [r#"{"msg":"안녕 🦀"}"#, r#"{"msg":"世界"}"#]
+        );
+    }
+
+    #[test]
+    fn finish_yields_unterminated_record() {
+        let mut d = NdjsonDecoder::new();
+        assert_eq!(texts(&d.push(b"1\n2")), ["1"]);
+        let last = d.finish().unwrap();
+        assert_eq!((last.line, last.text.as_str()), (2, "2"));
+        assert_eq!(d.finish(), None);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn parse_reports_line_number() {
+        let mut d = NdjsonDecoder::new();
+        let records = d.push(b"{\"n\":1}\n\n{\"n\":\n");
+
+        let ok: serde_json::Value = records[0].parse().unwrap();
+        assert_eq!(ok["n"], 1);
+
+        let err = records[1].parse::<serde_json::Value>().unwrap_err();
+        assert_eq!(err.line, 3);
+        assert!(alloc::format!("{err}").starts_with("line 3: "));
+    }
+}
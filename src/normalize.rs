@@ -0,0 +1,211 @@
+//! Streaming Unicode normalization (requires the `normalization` feature).
+//!
+//! Normalizing each chunk independently is wrong, because combining marks
+//! that belong to the last character of one chunk may arrive in the next.
+//! [`UnicodeNormalizer`] holds back the text after the last stable boundary
+//! until more input shows that it cannot change.
+
+use alloc::string::String;
+use core::iter;
+use core::mem;
+
+use unicode_normalization::char::canonical_combining_class;
+use unicode_normalization::{
+    is_nfc_quick, is_nfd_quick, is_nfkc_quick, is_nfkd_quick, IsNormalized, UnicodeNormalization,
+};
+
+use crate::{non_empty, Utf8Chunker};
+
+/// Held-back text longer than this is normalized and released even without
+/// a stable boundary, so a pathological run of combining marks cannot grow the
+/// buffer without limit.
+const MAX_HELD_BACK: usize = 1024;
+
+/// A Unicode normalization form, as defined by UAX #15.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum NormalizationForm {
+    /// Canonical composition.
+    #[default]
+    Nfc,
+    /// Canonical decomposition.
+    Nfd,
+    /// Compatibility composition.
+    Nfkc,
+    /// Compatibility decomposition.
+    Nfkd,
+}
+
+impl NormalizationForm {
+    /// Returns `true` if normalization never changes text across a boundary
+    /// before `c`.
+    fn is_boundary_before(self, c: char) -> bool {
+        if canonical_combining_class(c) != 0 {
+            return false;
+        }
+        let c = iter::once(c);
+        let quick = match self {
+            NormalizationForm::Nfc => is_nfc_quick(c),
+            NormalizationForm::Nfd => is_nfd_quick(c),
+            NormalizationForm::Nfkc => is_nfkc_quick(c),
+            NormalizationForm::Nfkd => is_nfkd_quick(c),
+        };
+        quick == IsNormalized::Yes
+    }
+
+    fn normalize_into(self, text: &str, out: &mut String) {
+        match self {
+            NormalizationForm::Nfc => out.extend(text.nfc()),
+            NormalizationForm::Nfd => out.extend(text.nfd()),
+            NormalizationForm::Nfkc => out.extend(text.nfkc()),
+            NormalizationForm::Nfkd => out.extend(text.nfkd()),
+        }
+    }
+}
+
+/// Incremental UTF-8 decoder that normalizes its output.
+///
+/// The text after the last stable boundary (a starter that cannot combine
+/// with anything before it) is held back until the next push or
+/// [`flush`](Self::flush).
+///
+/// # Examples
+///
+/// ```
+/// use utf8_chunked::{NormalizationForm, UnicodeNormalizer};
+///
+/// let mut normalizer = UnicodeNormalizer::new(NormalizationForm::Nfc);
+///
+/// // "cafe" followed by U+0301 COMBINING ACUTE ACCENT in the next chunk
+/// assert_eq!(normalizer.push(b"cafe"), Some("caf".to_string()));
+/// assert_eq!(normalizer.push("\u{301}!".as_bytes()), Some("é".to_string()));
+/// assert_eq!(normalizer.flush(), Some("!".to_string()));
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct UnicodeNormalizer {
+    chunker: Utf8Chunker,
+    form: NormalizationForm,
+    /// Decoded text after the last stable boundary, not yet normalized.
+    pending: String,
+}
+
+impl UnicodeNormalizer {
+    /// Creates a new `UnicodeNormalizer` producing the given form.
+    pub fn new(form: NormalizationForm) -> Self {
+        Self {
+            form,
+            ..Self::default()
+        }
+    }
+
+    /// Processes an incoming byte chunk and returns normalized text up to the
+    /// last stable boundary.
+    pub fn push(&mut self, data: &[u8]) -> Option<String> {
+        let text = self.chunker.push(data)?;
+        let mut input = mem::take(&mut self.pending);
+        input.push_str(&text);
+
+        let boundary = input
+            .char_indices()
+            .rev()
+            .find(|&(_, c)| self.form.is_boundary_before(c))
+            .map_or(0, |(i, _)| i);
+        let split = if input.len() - boundary > MAX_HELD_BACK {
+            input.len()
+        } else {
+            boundary
+        };
+
+        let mut out = String::with_capacity(split);
+        self.form.normalize_into(&input[..split], &mut out);
+        self.pending.push_str(&input[split..]);
+
+        non_empty(out)
+    }
+
+    /// Normalizes and returns all held-back text at the end of the stream.
+    ///
+    /// An incomplete UTF-8 sequence is replaced with U+FFFD first.
+    pub fn flush(&mut self) -> Option<String> {
+        if let Some(text) = self.chunker.flush() {
+            self.pending.push_str(&text);
+        }
+        if self.pending.is_empty() {
+            return None;
+        }
+        let mut out = String::with_capacity(self.pending.len());
+        self.form.normalize_into(&self.pending, &mut out);
+        self.pending.clear();
+        Some(out)
+    }
+
+    /// Returns `true` if no text is held back.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.chunker.is_empty() && self.pending.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize_split(form: NormalizationForm, input: &str, split: usize) -> String {
+        let mut n = UnicodeNormalizer::new(form);
+        let (a, b) = input.as_bytes().split_at(split);
+        let mut out = String::new();
+        for part in [a, b] {
+            if let Some(s) = n.push(part) {
+                out.push_str(&s);
+            }
+        }
+        if let Some(s) = n.flush() {
+            out.push_str(&s);
+        }
+        out
+    }
+
+    #[test]
+    fn matches_whole_string_normalization_at_every_split() {
+        // Decomposed Hangul jamo, combining marks out of canonical order, and a
+        // compatibility ligature
+        let input = "e\u{301}\u{1100}\u{1161}\u{11A8} a\u{323}\u{302} \u{FB01}x";
+        for form in [
+            NormalizationForm::Nfc,
+            NormalizationForm::Nfd,
+            NormalizationForm::Nfkc,
+            NormalizationForm::Nfkd,
+        ] {
+            let mut expected = String::new();
+            form.normalize_into(input, &mut expected);
+            for split in 0..=input.len() {
+                assert_eq!(
+                    normalize_split(form, input, split),
+                    expected,
+                    "{form:?} split={split}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn hangul_jamo_composed_across_pushes() {
+        let mut n = UnicodeNormalizer::new(NormalizationForm::Nfc);
+        // ᄒ + ᅡ + ᆫ = 한
+        assert_eq!(n.push("\u{1112}".as_bytes()), None);
+        assert_eq!(n.push("\u{1161}".as_bytes()), None);
+        assert_eq!(n.push("\u{11AB}.".as_bytes()), Some("한".into()));
+        assert_eq!(n.flush(), Some(".".into()));
+        assert!(n.is_empty());
+    }
+
+    #[test]
+    fn long_combining_run_is_released() {
+        let mut n = UnicodeNormalizer::new(NormalizationForm::Nfc);
+        let mut input = String::from("a");
+        for _ in 0..MAX_HELD_BACK {
+            input.push('\u{301}');
+        }
+        assert!(n.push(input.as_bytes()).is_some());
+        assert!(n.is_empty());
+    }
+}
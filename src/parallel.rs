@@ -0,0 +1,223 @@
+//! Splitting large buffers at character boundaries for parallel decoding.
+//!
+//! [`chunk_ranges`] divides a buffer into pieces that each start at a code
+//! point, so every piece can be validated independently. With the `rayon`
+//! feature, [`par_decode`] does exactly that on a thread pool and stitches the
+//! results back together.
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Range;
+
+#[cfg(feature = "rayon")]
+use alloc::string::String;
+
+use crate::is_continuation;
+#[cfg(feature = "rayon")]
+use crate::from_utf8;
+
+/// Splits `data` into at most `parts` contiguous ranges of roughly equal size,
+/// each starting at a code point.
+///
+/// Each nominal boundary is moved forward past at most 3 continuation bytes,
+/// so a valid multi-byte character is never divided between two ranges. The
+/// ranges cover all of `data` in order, and empty ranges are omitted.
+///
+/// # Examples
+///
+/// ```
+/// use utf8_chunked::chunk_ranges;
+///
+/// let data = "가나다라".as_bytes(); // 4 × 3 bytes
+/// let ranges = chunk_ranges(data, 2);
+/// assert_eq!(ranges, [0..6, 6..12]);
+///
+/// // A nominal boundary inside a character moves to the next character start
+/// let ranges = chunk_ranges(data, 3);
+/// assert_eq!(ranges, [0..6, 6..9, 9..12]);
+/// ```
+pub fn chunk_ranges(data: &[u8], parts: usize) -> Vec<Range<usize>> {
+    // More parts than bytes would only produce empty ranges
+    let parts = parts.clamp(1, data.len().max(1));
+    let mut ranges = Vec::with_capacity(parts);
+    let mut start = 0;
+
+    for i in 1..=parts {
+        // Widened so that `len * i` cannot overflow for huge inputs
+        let mut end = (data.len() as u128 * i as u128 / parts as u128) as usize;
+        let limit = (end + 3).min(data.len());
+        while end < limit && is_continuation(data[end]) {
+            end += 1;
+        }
+        if end > start {
+            ranges.push(start..end);
+            start = end;
+        }
+    }
+    ranges
+}
+
+/// Error returned by [`par_decode`], with offsets relative to the whole input.
+///
+/// This mirrors [`core::str::Utf8Error`], which cannot be constructed outside
+/// the standard library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParDecodeError {
+    valid_up_to: usize,
+    error_len: Option<usize>,
+}
+
+impl ParDecodeError {
+    /// Returns the absolute offset of the first invalid byte; everything before
+    /// it is valid UTF-8.
+    #[inline]
+    pub fn valid_up_to(&self) -> usize {
+        self.valid_up_to
+    }
+
+    /// Returns the length of the invalid sequence, or `None` if the input ends
+    /// with an incomplete character.
+    #[inline]
+    pub fn error_len(&self) -> Option<usize> {
+        self.error_len
+    }
+}
+
+impl fmt::Display for ParDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.error_len {
+            Some(len) => write!(
+                f,
+                "invalid utf-8 sequence of {len} bytes from index {}",
+                self.valid_up_to
+            ),
+            None => write!(
+                f,
+                "incomplete utf-8 byte sequence from index {}",
+                self.valid_up_to
+            ),
+        }
+    }
+}
+
+impl core::error::Error for ParDecodeError {}
+
+/// Validates and decodes `data` in parallel on the rayon thread pool
+/// (requires the `rayon` feature).
+///
+/// `data` is divided into `threads` pieces with [`chunk_ranges`], each piece is
+/// validated on its own, and the results are joined into one `String`. On
+/// failure the error reports the absolute offset of the *first* invalid
+/// sequence, exactly as [`core::str::from_utf8`] would for the whole buffer.
+///
+/// # Examples
+///
+/// ```
+/// use utf8_chunked::par_decode;
+///
+/// let text = "한국어 🦀 ".repeat(1000);
+/// assert_eq!(par_decode(text.as_bytes(), 8).unwrap(), text);
+///
+/// let mut bytes = text.into_bytes();
+/// bytes[5000] = 0xFF;
+/// assert_eq!(par_decode(&bytes, 8).unwrap_err().valid_up_to(), 4998);
+/// ```
+#[cfg(feature = "rayon")]
+pub fn par_decode(data: &[u8], threads: usize) -> Result<String, ParDecodeError> {
+    use rayon::prelude::*;
+
+    let ranges = chunk_ranges(data, threads);
+    let pieces: Vec<Result<&str, usize>> = ranges
+        .par_iter()
+        .map(|range| from_utf8(&data[range.clone()]).map_err(|e| range.start + e.valid_up_to()))
+        .collect();
+
+    let mut out = String::with_capacity(data.len());
+    for piece in pieces {
+        match piece {
+            Ok(text) => out.push_str(text),
+            Err(valid_up_to) => {
+                // A piece may end in the middle of an invalid sequence, so
+                // measure the error against the rest of the whole input
+                let window = &data[valid_up_to..(valid_up_to + 4).min(data.len())];
+                let error_len = from_utf8(window).err().and_then(|e| e.error_len());
+                return Err(ParDecodeError {
+                    valid_up_to,
+                    error_len,
+                });
+            }
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranges_cover_input_at_char_starts() {
+        let text = "a한🦀bé국어 mixed 世界";
+        let data = text.as_bytes();
+        for parts in 1..=data.len() + 2 {
+            let ranges = chunk_ranges(data, parts);
+            assert!(ranges.len() <= parts);
+            assert_eq!(ranges.first().map(|r| r.start), Some(0));
+            assert_eq!(ranges.last().map(|r| r.end), Some(data.len()));
+            for pair in ranges.windows(2) {
+                assert_eq!(pair[0].end, pair[1].start);
+            }
+            for r in &ranges {
+                assert!(!r.is_empty());
+                assert!(text.is_char_boundary(r.start), "parts={parts}");
+            }
+        }
+    }
+
+    #[test]
+    fn empty_input() {
+        assert!(chunk_ranges(b"", 4).is_empty());
+        let ranges = chunk_ranges(b"abc", 0);
+        assert_eq!((ranges.len(), ranges.first()), (1, Some(&(0..3))));
+    }
+
+    #[test]
+    fn huge_part_count_is_clamped() {
+        let data = "가나다".as_bytes();
+        assert_eq!(chunk_ranges(data, usize::MAX), [0..3, 3..6, 6..9]);
+        assert_eq!(chunk_ranges(b"", usize::MAX), []);
+        #[cfg(feature = "rayon")]
+        assert_eq!(par_decode(data, usize::MAX).unwrap(), "가나다");
+    }
+
+    #[test]
+    fn long_continuation_run_still_makes_progress() {
+        let data = [0x80; 16];
+        let ranges = chunk_ranges(&data, 4);
+        assert_eq!(ranges.last().map(|r| r.end), Some(16));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_decode_matches_from_utf8() {
+        let text = "한국어 🦀 é mixed 世界\n".repeat(50);
+        for threads in [1, 2, 3, 7, 64] {
+            assert_eq!(par_decode(text.as_bytes(), threads).as_deref(), Ok(&*text));
+        }
+
+        let cases: [&[u8]; 4] = [
+            b"abc\xFFdef",
+            b"abc\xE4\xB8def",
+            b"abcdef\xF0\x9F",
+            b"\x80abcdefgh",
+        ];
+        for data in cases {
+            let expected = core::str::from_utf8(data).unwrap_err();
+            for threads in 1..=data.len() {
+                let err = par_decode(data, threads).unwrap_err();
+                assert_eq!(err.valid_up_to(), expected.valid_up_to(), "{data:?}");
+                assert_eq!(err.error_len(), expected.error_len(), "{data:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,97 @@
+//! Merged stdout/stderr decoding for child processes (requires the `tokio`
+//! feature).
+
+use std::io;
+use std::process::{ExitStatus, Stdio};
+
+use futures::stream::{self, StreamExt};
+use tokio::io::AsyncRead;
+use tokio::process::{Child, Command};
+use tokio_stream::Stream;
+
+use crate::utf8_safe_stream;
+
+/// Which output stream of a child process some text came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StreamKind {
+    /// Standard output.
+    Stdout,
+    /// Standard error.
+    Stderr,
+}
+
+/// An item produced by [`child_output`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChildEvent {
+    /// Text decoded from one of the child's output streams.
+    Output(StreamKind, String),
+    /// The child exited. Always the last item.
+    Exit(ExitStatus),
+}
+
+/// Interleaves the decoded stdout and stderr of `child`, followed by its exit
+/// status.
+///
+/// Each stream is decoded with its own [`Utf8Chunker`](crate::Utf8Chunker),
+/// so a character split across reads on one stream is never corrupted by
+/// output arriving on the other. Text is yielded in the order it is read.
+/// Streams that were not piped are skipped.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::process::Stdio;
+/// use tokio::process::Command;
+/// use tokio_stream::StreamExt;
+/// use utf8_chunked::{child_output, ChildEvent, StreamKind};
+///
+/// # async fn example() -> std::io::Result<()> {
+/// let child = Command::new("cargo")
+///     .arg("build")
+///     .stdout(Stdio::piped())
+///     .stderr(Stdio::piped())
+///     .spawn()?;
+///
+/// let events = child_output(child);
+/// tokio::pin!(events);
+/// while let Some(event) = events.next().await {
+///     match event? {
+///         ChildEvent::Output(StreamKind::Stdout, text) => print!("{text}"),
+///         ChildEvent::Output(StreamKind::Stderr, text) => eprint!("{text}"),
+///         ChildEvent::Exit(status) => println!("exited with {status}"),
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn child_output(mut child: Child) -> impl Stream<Item = io::Result<ChildEvent>> {
+    let stdout = tagged(child.stdout.take(), StreamKind::Stdout);
+    let stderr = tagged(child.stderr.take(), StreamKind::Stderr);
+    let exit = stream::once(async move { child.wait().await.map(ChildEvent::Exit) });
+
+    stream::select(stdout, stderr).chain(exit)
+}
+
+/// Spawns `command` with piped stdout and stderr and returns
+/// [`child_output`] for it.
+///
+/// Stdin is left as configured on `command`.
+pub fn spawn_output(
+    command: &mut Command,
+) -> io::Result<impl Stream<Item = io::Result<ChildEvent>>> {
+    let child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    Ok(child_output(child))
+}
+
+/// Decodes `reader`, if present, tagging its text with `kind`.
+fn tagged<R>(reader: Option<R>, kind: StreamKind) -> impl Stream<Item = io::Result<ChildEvent>>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    stream::iter(reader).flat_map(move |reader| {
+        utf8_safe_stream(reader).map(move |text| text.map(|text| ChildEvent::Output(kind, text)))
+    })
+}
@@ -0,0 +1,329 @@
+//! Running commands in a pseudo-terminal (requires the `pty` feature, Linux
+//! only).
+//!
+//! Many programs only colourize or line-buffer their output when attached to
+//! a TTY. [`spawn_pty`] runs a command with a new pseudo-terminal as its
+//! controlling terminal and decodes what it writes; [`PtyProcess::writer`]
+//! sends it input.
+
+use std::io;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::pin::Pin;
+use std::process::{ExitStatus, Stdio};
+use std::task::{ready, Context, Poll};
+
+use tokio::io::unix::AsyncFd;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::process::{Child, Command};
+use tokio_stream::Stream;
+
+use crate::utf8_safe_stream;
+
+/// The size of a pseudo-terminal window, in character cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PtySize {
+    /// Number of rows.
+    pub rows: u16,
+    /// Number of columns.
+    pub cols: u16,
+}
+
+impl Default for PtySize {
+    /// The classic 80×24 terminal.
+    fn default() -> Self {
+        Self { rows: 24, cols: 80 }
+    }
+}
+
+impl PtySize {
+    fn to_winsize(self) -> libc::winsize {
+        libc::winsize {
+            ws_row: self.rows,
+            ws_col: self.cols,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        }
+    }
+}
+
+/// Options for [`spawn_pty`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PtyOptions {
+    /// Initial window size.
+    pub size: PtySize,
+    /// Keep the terminal's output translation of `\n` to `\r\n` (`ONLCR`).
+    ///
+    /// Off by default, so output contains the newlines the program actually
+    /// wrote. A lone `\r`, as used by progress bars, is passed through either
+    /// way.
+    pub crlf: bool,
+}
+
+/// A child process running in a pseudo-terminal, returned by [`spawn_pty`].
+#[derive(Debug)]
+pub struct PtyProcess {
+    child: Child,
+    master: OwnedFd,
+}
+
+impl PtyProcess {
+    /// Changes the terminal window size; the child receives `SIGWINCH`.
+    pub fn resize(&self, size: PtySize) -> io::Result<()> {
+        set_window_size(self.master.as_raw_fd(), size)
+    }
+
+    /// Waits for the child to exit.
+    pub async fn wait(&mut self) -> io::Result<ExitStatus> {
+        self.child.wait().await
+    }
+
+    /// Returns the underlying child process, e.g. to kill it.
+    #[inline]
+    pub fn child_mut(&mut self) -> &mut Child {
+        &mut self.child
+    }
+
+    /// Returns a writer that sends input to the terminal, as if typed.
+    ///
+    /// The terminal echoes input back to the output stream unless the child
+    /// turns echo off. There is no separate end of input: write the
+    /// terminal's EOF character (`\x04`, Ctrl-D) at the start of a line
+    /// instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a Tokio runtime with IO enabled.
+    pub fn writer(&self) -> io::Result<PtyWriter> {
+        Ok(PtyWriter(AsyncFd::new(self.master.try_clone()?)?))
+    }
+}
+
+/// Spawns `command` in a new pseudo-terminal and returns the process and a
+/// stream of its decoded output.
+///
+/// The command's stdin, stdout and stderr are all attached to the terminal,
+/// so its output arrives interleaved on the one stream, decoded with the same
+/// semantics as [`utf8_safe_stream`]. The stream ends once the child and any
+/// processes it started have closed the terminal.
+///
+/// This sets up `command` for this one spawn, like
+/// [`Command::spawn`](tokio::process::Command::spawn) with its stdio: it adds a
+/// `pre_exec` hook that makes the terminal the child's controlling TTY, and
+/// afterwards its stdin, stdout and stderr are set to null. Spawning it again
+/// fails.
+///
+/// The terminal is read without blocking through the Tokio reactor, so
+/// dropping the stream stops reading right away.
+///
+/// # Panics
+///
+/// Panics if called outside a Tokio runtime with IO enabled.
+///
+/// # Examples
+///
+/// ```no_run
+/// use tokio::process::Command;
+/// use tokio_stream::StreamExt;
+/// use utf8_chunked::{spawn_pty, PtyOptions, PtySize};
+///
+/// # async fn example() -> std::io::Result<()> {
+/// let mut command = Command::new("ls");
+/// command.arg("--color=auto");
+/// let options = PtyOptions {
+///     size: PtySize { rows: 40, cols: 120 },
+///     ..PtyOptions::default()
+/// };
+/// let (mut process, output) = spawn_pty(&mut command, options)?;
+/// tokio::pin!(output);
+/// while let Some(text) = output.next().await {
+///     print!("{}", text?);
+/// }
+/// process.wait().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn spawn_pty(
+    command: &mut Command,
+    options: PtyOptions,
+) -> io::Result<(PtyProcess, impl Stream<Item = io::Result<String>>)> {
+    let (master, slave) = open_pty(options)?;
+
+    command
+        .stdin(Stdio::from(slave.try_clone()?))
+        .stdout(Stdio::from(slave.try_clone()?))
+        .stderr(Stdio::from(slave));
+    // Safety: only async-signal-safe calls between fork and exec
+    unsafe {
+        command.pre_exec(|| {
+            // Start a new session with the terminal as its controlling TTY
+            if libc::setsid() == -1 || libc::ioctl(0, libc::TIOCSCTTY, 0) == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+    let child = command.spawn();
+    // Close the parent's copies of the slave, so reads on the master end
+    // with the child
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    let child = child?;
+
+    let reader = PtyReader(AsyncFd::new(master.try_clone()?)?);
+    Ok((PtyProcess { child, master }, utf8_safe_stream(reader)))
+}
+
+/// Opens a pseudo-terminal pair, returning `(master, slave)`.
+fn open_pty(options: PtyOptions) -> io::Result<(OwnedFd, OwnedFd)> {
+    let mut master: RawFd = -1;
+    let mut slave: RawFd = -1;
+    let winsize = options.size.to_winsize();
+    // Safety: the out-pointers are valid and the name buffer is unused
+    let ret = unsafe {
+        libc::openpty(
+            &mut master,
+            &mut slave,
+            core::ptr::null_mut(),
+            core::ptr::null(),
+            &winsize,
+        )
+    };
+    if ret == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    // Safety: openpty returned two new descriptors that we now own
+    let (master, slave) = unsafe { (OwnedFd::from_raw_fd(master), OwnedFd::from_raw_fd(slave)) };
+
+    set_cloexec(master.as_raw_fd())?;
+    set_cloexec(slave.as_raw_fd())?;
+    set_nonblocking(master.as_raw_fd())?;
+    if !options.crlf {
+        disable_onlcr(slave.as_raw_fd())?;
+    }
+    Ok((master, slave))
+}
+
+fn set_cloexec(fd: RawFd) -> io::Result<()> {
+    // Safety: fd is a valid descriptor for the duration of the call
+    if unsafe { libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+fn set_nonblocking(fd: RawFd) -> io::Result<()> {
+    // Safety: fd is a valid descriptor for the duration of the calls
+    unsafe {
+        let flags = libc::fcntl(fd, libc::F_GETFL);
+        if flags == -1 || libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK) == -1 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+fn disable_onlcr(fd: RawFd) -> io::Result<()> {
+    // Safety: termios is plain data, filled in by tcgetattr before use
+    unsafe {
+        let mut termios: libc::termios = core::mem::zeroed();
+        if libc::tcgetattr(fd, &mut termios) == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        termios.c_oflag &= !libc::ONLCR;
+        if libc::tcsetattr(fd, libc::TCSANOW, &termios) == -1 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+fn set_window_size(fd: RawFd, size: PtySize) -> io::Result<()> {
+    let winsize = size.to_winsize();
+    // Safety: fd is a valid terminal descriptor and winsize outlives the call
+    if unsafe { libc::ioctl(fd, libc::TIOCSWINSZ, &winsize) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Reads the non-blocking master side of a pseudo-terminal.
+///
+/// Linux reports `EIO` instead of end-of-file once every slave descriptor has
+/// been closed, so that is mapped to a clean EOF.
+struct PtyReader(AsyncFd<OwnedFd>);
+
+impl AsyncRead for PtyReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        loop {
+            let mut guard = ready!(self.0.poll_read_ready(cx))?;
+            let unfilled = buf.initialize_unfilled();
+            let result = guard.try_io(|fd| {
+                // Safety: unfilled is valid for writes of its length
+                let n = unsafe {
+                    libc::read(fd.as_raw_fd(), unfilled.as_mut_ptr().cast(), unfilled.len())
+                };
+                if n == -1 {
+                    Err(io::Error::last_os_error())
+                } else {
+                    Ok(n as usize)
+                }
+            });
+            match result {
+                Ok(Ok(n)) => {
+                    buf.advance(n);
+                    return Poll::Ready(Ok(()));
+                }
+                Ok(Err(e)) if e.raw_os_error() == Some(libc::EIO) => return Poll::Ready(Ok(())),
+                Ok(Err(e)) => return Poll::Ready(Err(e)),
+                // Spurious readiness: wait again
+                Err(_would_block) => continue,
+            }
+        }
+    }
+}
+
+/// Writes input to a pseudo-terminal, returned by [`PtyProcess::writer`].
+#[derive(Debug)]
+pub struct PtyWriter(AsyncFd<OwnedFd>);
+
+impl AsyncWrite for PtyWriter {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        loop {
+            let mut guard = ready!(self.0.poll_write_ready(cx))?;
+            let result = guard.try_io(|fd| {
+                // Safety: buf is valid for reads of its length
+                let n = unsafe { libc::write(fd.as_raw_fd(), buf.as_ptr().cast(), buf.len()) };
+                if n == -1 {
+                    Err(io::Error::last_os_error())
+                } else {
+                    Ok(n as usize)
+                }
+            });
+            match result {
+                Ok(result) => return Poll::Ready(result),
+                // Spurious readiness: wait again
+                Err(_would_block) => continue,
+            }
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        // Writes go straight to the terminal
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        // A terminal cannot be half-closed; see `PtyProcess::writer`
+        Poll::Ready(Ok(()))
+    }
+}
@@ -0,0 +1,208 @@
+//! Backwards incremental decoding, for reading the end of a file first.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{is_continuation, non_empty, split_valid};
+
+/// Incremental UTF-8 decoder for chunks that arrive in reverse file order.
+///
+/// To show the last lines of a large log, a reader can read it backwards in
+/// blocks. Each block may then begin with continuation bytes of a character
+/// whose leading byte is at the end of the *next* block pushed. Those bytes
+/// (at most 3) are carried and appended to that block, so every character is
+/// decoded whole.
+///
+/// The text returned by each [`push`](Self::push) is in normal reading order;
+/// prepend it to the text from earlier pushes. Invalid sequences are dropped,
+/// as [`Utf8Chunker`](crate::Utf8Chunker) drops them; only a character cut off
+/// at an edge of the file becomes a single U+FFFD.
+///
+/// # Examples
+///
+/// ```
+/// use utf8_chunked::ReverseUtf8Chunker;
+///
+/// // "a한b" = 61 ED 95 9C 62, read backwards in blocks of 3 bytes
+/// let mut chunker = ReverseUtf8Chunker::new();
+/// assert_eq!(chunker.push(&[0x95, 0x9C, b'b']), Some("b".to_string()));
+/// assert_eq!(chunker.push(&[b'a', 0xED]), Some("a한".to_string()));
+/// assert_eq!(chunker.flush(), None);
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ReverseUtf8Chunker {
+    /// Leading continuation bytes of the previously pushed (later) block.
+    carry: Vec<u8>,
+    /// Some bytes after `carry` have been decoded, so the next block does not
+    /// end at the end of the file.
+    started: bool,
+}
+
+impl ReverseUtf8Chunker {
+    /// Creates a new `ReverseUtf8Chunker` with an empty buffer.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            carry: Vec::new(),
+            started: false,
+        }
+    }
+
+    /// Processes the block that precedes all previously pushed blocks and
+    /// returns its complete text.
+    ///
+    /// Returns `None` if the block (together with the carried bytes) consists
+    /// only of continuation bytes that are carried into the next push.
+    pub fn push(&mut self, data: &[u8]) -> Option<String> {
+        if data.is_empty() {
+            return None;
+        }
+
+        let mut block = Vec::with_capacity(data.len() + self.carry.len());
+        block.extend_from_slice(data);
+        block.append(&mut self.carry);
+
+        // A character can have at most 3 continuation bytes, so only those
+        // can still be completed by an earlier block
+        let orphans = block.iter().take_while(|&&b| is_continuation(b)).count();
+        let carried = orphans.min(3);
+        self.carry.extend_from_slice(&block[..carried]);
+
+        let rest = &block[carried..];
+        if rest.is_empty() {
+            return None;
+        }
+        let at_end = !core::mem::replace(&mut self.started, true);
+        non_empty(decode(rest, at_end))
+    }
+
+    /// Flushes carried bytes at the start of the file.
+    ///
+    /// They are the end of a character cut off by the start of the file, so
+    /// they are replaced with one U+FFFD. Returns `None` if nothing is
+    /// carried.
+    pub fn flush(&mut self) -> Option<String> {
+        if self.carry.is_empty() {
+            return None;
+        }
+        self.carry.clear();
+        Some("\u{FFFD}".into())
+    }
+
+    /// Returns `true` if no bytes are carried.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.carry.is_empty()
+    }
+
+    /// Returns the number of bytes currently carried.
+    #[inline]
+    pub fn buffered_len(&self) -> usize {
+        self.carry.len()
+    }
+
+    /// Discards any carried bytes, returning the chunker to its initial state.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.carry.clear();
+        self.started = false;
+    }
+}
+
+/// Decodes `data`, dropping invalid sequences.
+///
+/// An incomplete character at the end is replaced with U+FFFD if `data` ends
+/// at the end of the file. Otherwise a non-continuation byte follows it, so it
+/// is invalid and dropped.
+fn decode(mut data: &[u8], at_end: bool) -> String {
+    let mut out = String::with_capacity(data.len());
+    loop {
+        let (valid_len, error_len) = split_valid(data);
+        // Safety: split_valid only reports validated prefixes
+        out.push_str(unsafe { core::str::from_utf8_unchecked(&data[..valid_len]) });
+        match error_len {
+            Some(len) => data = &data[valid_len + len..],
+            None => {
+                if at_end && valid_len < data.len() {
+                    out.push('\u{FFFD}');
+                }
+                return out;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Decodes `input` backwards in `block`-byte blocks, aligned to the end.
+    fn decode_backwards(input: &[u8], block: usize) -> String {
+        let mut c = ReverseUtf8Chunker::new();
+        let mut pieces = Vec::new();
+        let mut end = input.len();
+        while end > 0 {
+            let start = end.saturating_sub(block);
+            pieces.extend(c.push(&input[start..end]));
+            end = start;
+        }
+        pieces.extend(c.flush());
+        pieces.reverse();
+        pieces.concat()
+    }
+
+    #[test]
+    fn matches_forward_decoding_for_every_block_size() {
+        let inputs: [&[u8]; 3] = [
+            "log: 한국어 🦀 é\nnext line 世界\n".as_bytes(),
+            // A stray byte, a cut-off '한', an overlong encoding, a crab with
+            // extra continuation bytes, and a cut-off '한' at the end
+            b"ok \xFF a\xED\x95 b \xE0\x80\x80 \xF0\x9F\xA6\x80\x80\x80 end \xED\x95",
+            b"\xC3\xA9\xC3\xF0\x9F\xA6\x80\xC3",
+        ];
+        for input in inputs {
+            let mut forward = crate::Utf8Chunker::new();
+            let expected: String = forward
+                .push(input)
+                .into_iter()
+                .chain(forward.flush())
+                .collect();
+            for block in 1..=input.len() {
+                assert_eq!(decode_backwards(input, block), expected, "block={block}");
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_bytes_are_dropped_mid_stream() {
+        assert_eq!(decode_backwards(b"a\xFF\xFEb\xED\x95c", 2), "abc");
+        // Only the cut-off character at the end of the file is replaced
+        assert_eq!(decode_backwards(b"a\xFFb\xED\x95", 1), "ab\u{FFFD}");
+    }
+
+    #[test]
+    fn carries_at_most_three_bytes() {
+        // '🦀' = F0 9F A6 80, followed by a stray continuation byte
+        let mut c = ReverseUtf8Chunker::new();
+        assert_eq!(c.push(&[0xA6, 0x80, 0x80]), None);
+        assert_eq!(c.push(&[0x9F]), None);
+        assert_eq!(c.buffered_len(), 3);
+        assert_eq!(c.push(&[b'a', 0xF0]), Some("a🦀".into()));
+        assert!(c.is_empty());
+    }
+
+    #[test]
+    fn orphans_at_file_start_are_replaced_once() {
+        // The file starts in the middle of '한'
+        assert_eq!(decode_backwards(&[0x95, 0x9C, b'x'], 2), "\u{FFFD}x");
+    }
+
+    #[test]
+    fn reset_discards_carry() {
+        let mut c = ReverseUtf8Chunker::new();
+        c.push(&[0x9C]);
+        c.reset();
+        assert!(c.is_empty());
+        assert_eq!(c.flush(), None);
+    }
+}
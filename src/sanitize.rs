@@ -0,0 +1,181 @@
+//! Sanitizing of untrusted text before it is shown in a terminal.
+//!
+//! Raw control characters let untrusted output move the cursor, rewrite the
+//! window title or hide text, and bidirectional overrides can make displayed
+//! text differ from its logical order ("Trojan Source", CVE-2021-42574).
+
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+/// What [`TerminalSanitizer`] does with a dangerous character.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SanitizeMode {
+    /// Replace it with a visible escape such as `\u{1b}`.
+    #[default]
+    Escape,
+    /// Remove it.
+    Remove,
+    /// Replace it with U+FFFD.
+    Replace,
+}
+
+/// Escapes or removes control characters and other dangerous code points.
+///
+/// By default the following are treated as dangerous:
+///
+/// - C0 controls (U+0000–U+001F) except `\t` and `\n`, DEL (U+007F) and C1
+///   controls (U+0080–U+009F), including ESC
+/// - bidirectional formatting characters: U+061C, U+200E, U+200F,
+///   U+202A–U+202E and U+2066–U+2069
+/// - the line and paragraph separators U+2028 and U+2029
+///
+/// Apply it to the output of [`Utf8Chunker`](crate::Utf8Chunker) or another
+/// decoding stage: that output only ever contains whole characters, so a
+/// dangerous character can never slip through by being split across chunks.
+///
+/// # Examples
+///
+/// ```
+/// use utf8_chunked::{SanitizeMode, TerminalSanitizer};
+///
+/// let sanitizer = TerminalSanitizer::new();
+/// assert_eq!(sanitizer.sanitize("ok\x1b]0;pwned\x07"), "ok\\u{1b}]0;pwned\\u{7}");
+///
+/// let sanitizer = TerminalSanitizer::new().mode(SanitizeMode::Remove).allow('\r');
+/// assert_eq!(sanitizer.sanitize("a\r\nb\u{202E}c"), "a\r\nbc");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TerminalSanitizer {
+    mode: SanitizeMode,
+    allow: Vec<char>,
+}
+
+impl Default for TerminalSanitizer {
+    fn default() -> Self {
+        Self {
+            mode: SanitizeMode::default(),
+            allow: alloc::vec!['\t', '\n'],
+        }
+    }
+}
+
+impl TerminalSanitizer {
+    /// Creates a sanitizer that escapes dangerous characters and allows `\t`
+    /// and `\n`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets what to do with dangerous characters.
+    pub fn mode(mut self, mode: SanitizeMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Allows `c` through unchanged even if it would otherwise be dangerous.
+    pub fn allow(mut self, c: char) -> Self {
+        if !self.allow.contains(&c) {
+            self.allow.push(c);
+        }
+        self
+    }
+
+    /// Clears the allow-list, including the default `\t` and `\n`.
+    pub fn deny_all(mut self) -> Self {
+        self.allow.clear();
+        self
+    }
+
+    /// Returns `true` if `c` would be escaped or removed.
+    pub fn is_dangerous(&self, c: char) -> bool {
+        is_dangerous(c) && !self.allow.contains(&c)
+    }
+
+    /// Sanitizes `text`, borrowing it unchanged if it contains nothing dangerous.
+    pub fn sanitize<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let Some(first) = text.find(|c| self.is_dangerous(c)) else {
+            return Cow::Borrowed(text);
+        };
+
+        let mut out = String::with_capacity(text.len() + 8);
+        out.push_str(&text[..first]);
+        for c in text[first..].chars() {
+            if !self.is_dangerous(c) {
+                out.push(c);
+                continue;
+            }
+            match self.mode {
+                SanitizeMode::Escape => {
+                    let _ = write!(out, "{}", c.escape_unicode());
+                }
+                SanitizeMode::Remove => {}
+                SanitizeMode::Replace => out.push('\u{FFFD}'),
+            }
+        }
+        Cow::Owned(out)
+    }
+}
+
+/// Returns `true` for the code points that are dangerous by default.
+fn is_dangerous(c: char) -> bool {
+    c.is_control()
+        || matches!(
+            c,
+            '\u{061C}'
+                | '\u{200E}'
+                | '\u{200F}'
+                | '\u{202A}'..='\u{202E}'
+                | '\u{2066}'..='\u{2069}'
+                | '\u{2028}'
+                | '\u{2029}'
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Utf8Chunker;
+
+    #[test]
+    fn clean_text_is_borrowed() {
+        let s = TerminalSanitizer::new();
+        assert!(matches!(s.sanitize("한글\ttext\n"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn escapes_c0_c1_and_del() {
+        let s = TerminalSanitizer::new();
+        assert_eq!(s.sanitize("a\x00b\x7fc\u{9b}d"), "a\\u{0}b\\u{7f}c\\u{9b}d");
+    }
+
+    #[test]
+    fn trojan_source_bidi_overrides() {
+        let s = TerminalSanitizer::new().mode(SanitizeMode::Replace);
+        assert_eq!(
+            s.sanitize("access\u{202E}\u{2066}level\u{2069}\u{2028}"),
+            "access\u{FFFD}\u{FFFD}level\u{FFFD}\u{FFFD}"
+        );
+    }
+
+    #[test]
+    fn allow_list() {
+        let s = TerminalSanitizer::new().deny_all().allow('\x1b');
+        assert_eq!(s.sanitize("\x1b[1m\n"), "\x1b[1m\\u{a}");
+    }
+
+    #[test]
+    fn split_c1_control_is_caught() {
+        // U+009B (8-bit CSI) = C2 9B, split across chunks
+        let s = TerminalSanitizer::new().mode(SanitizeMode::Remove);
+        let mut c = Utf8Chunker::new();
+        let mut out = String::new();
+        for chunk in [&b"x\xC2"[..], &b"\x9B31my"[..]] {
+            if let Some(text) = c.push(chunk) {
+                out.push_str(&s.sanitize(&text));
+            }
+        }
+        assert_eq!(out, "x31my");
+    }
+}
@@ -0,0 +1,255 @@
+//! Bounded line-oriented scrollback for terminal-like views.
+
+use alloc::collections::VecDeque;
+use alloc::string::String;
+
+use crate::Utf8Chunker;
+
+/// Keeps the most recent lines of a decoded byte stream, bounded by both a
+/// line count and a byte count.
+///
+/// Bytes are decoded with a [`Utf8Chunker`], so characters split across pushes
+/// are stored whole. Text is split into lines on `\n`; a `\r\n` terminator is
+/// removed like `\n`, and the text after the last `\n` is kept as an
+/// unfinished last line that later pushes extend.
+///
+/// When either limit is exceeded, the oldest whole lines are evicted. Only a
+/// single line longer than `max_bytes` on its own is shortened instead, by
+/// dropping characters from its start, so memory stays bounded even for
+/// output that never contains a newline. The byte count covers line text and
+/// not line terminators.
+///
+/// # Examples
+///
+/// ```
+/// use utf8_chunked::TextScrollback;
+///
+/// let mut scrollback = TextScrollback::new(2, 1024);
+/// scrollback.push(b"one\ntwo\nth\xED\x95");
+/// scrollback.push(b"\x9Cree");
+///
+/// let lines: Vec<&str> = scrollback.lines().collect();
+/// assert_eq!(lines, ["two", "th한ree"]);
+/// assert_eq!(scrollback.evicted_lines(), 1);
+/// assert_eq!(scrollback.snapshot(), "two\nth한ree");
+/// ```
+#[derive(Debug, Clone)]
+pub struct TextScrollback {
+    chunker: Utf8Chunker,
+    /// Complete lines, without terminators.
+    lines: VecDeque<String>,
+    /// Text after the last `\n`.
+    partial: String,
+    /// Total length of `lines` and `partial` in bytes.
+    len: usize,
+    max_lines: usize,
+    max_bytes: usize,
+    evicted: u64,
+}
+
+impl TextScrollback {
+    /// Creates an empty scrollback that keeps at most `max_lines` lines and
+    /// `max_bytes` bytes of text.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_lines` or `max_bytes` is 0.
+    pub fn new(max_lines: usize, max_bytes: usize) -> Self {
+        assert!(max_lines > 0, "max_lines must be at least 1");
+        assert!(max_bytes > 0, "max_bytes must be at least 1");
+        Self {
+            chunker: Utf8Chunker::new(),
+            lines: VecDeque::new(),
+            partial: String::new(),
+            len: 0,
+            max_lines,
+            max_bytes,
+            evicted: 0,
+        }
+    }
+
+    /// Decodes `data` and appends its complete characters.
+    pub fn push(&mut self, data: &[u8]) {
+        if let Some(text) = self.chunker.push(data) {
+            self.push_str(&text);
+        }
+    }
+
+    /// Appends already decoded text, e.g. the output of a [`Utf8Chunker`]
+    /// shared with another consumer.
+    pub fn push_str(&mut self, text: &str) {
+        let mut rest = text;
+        while let Some(newline) = rest.find('\n') {
+            self.partial.push_str(&rest[..newline]);
+            self.len += newline;
+            let mut line = core::mem::take(&mut self.partial);
+            if line.ends_with('\r') {
+                line.pop();
+                self.len -= 1;
+            }
+            self.lines.push_back(line);
+            self.evict();
+            rest = &rest[newline + 1..];
+        }
+        self.partial.push_str(rest);
+        self.len += rest.len();
+        self.evict();
+    }
+
+    /// Appends U+FFFD if the decoder is holding an incomplete character, as
+    /// [`Utf8Chunker::flush`] does.
+    pub fn flush(&mut self) {
+        if let Some(text) = self.chunker.flush() {
+            self.push_str(&text);
+        }
+    }
+
+    /// Returns the number of stored lines, including an unfinished last line.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.lines.len() + usize::from(!self.partial.is_empty())
+    }
+
+    /// Returns `true` if no text is stored.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of bytes of stored text, excluding line terminators.
+    #[inline]
+    pub fn byte_len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the number of whole lines evicted so far.
+    ///
+    /// Adding this to the index of a line from [`lines`](Self::lines) gives
+    /// its line number in the whole stream.
+    #[inline]
+    pub fn evicted_lines(&self) -> u64 {
+        self.evicted
+    }
+
+    /// Iterates over the stored lines, oldest first, without terminators.
+    ///
+    /// The last item is the unfinished last line, if there is one. Use
+    /// `.rev()` to walk back from the newest line.
+    pub fn lines(&self) -> impl DoubleEndedIterator<Item = &str> + '_ {
+        let partial = Some(self.partial.as_str()).filter(|line| !line.is_empty());
+        self.lines.iter().map(String::as_str).chain(partial)
+    }
+
+    /// Returns the stored text as one string, with `\n` after every complete
+    /// line.
+    pub fn snapshot(&self) -> String {
+        let mut out = String::with_capacity(self.len + self.lines.len());
+        for line in &self.lines {
+            out.push_str(line);
+            out.push('\n');
+        }
+        out.push_str(&self.partial);
+        out
+    }
+
+    /// Removes all stored text and any incomplete character, keeping the
+    /// eviction count.
+    pub fn clear(&mut self) {
+        self.chunker.reset();
+        self.lines.clear();
+        self.partial.clear();
+        self.len = 0;
+    }
+
+    fn evict(&mut self) {
+        while self.len() > self.max_lines || self.len > self.max_bytes {
+            if self.len() > 1 {
+                // More than one line means at least one complete line
+                if let Some(line) = self.lines.pop_front() {
+                    self.len -= line.len();
+                    self.evicted += 1;
+                }
+                continue;
+            }
+            // A single line over the byte limit: drop its oldest characters
+            let line = self.lines.front_mut().unwrap_or(&mut self.partial);
+            let mut cut = self.len - self.max_bytes;
+            while !line.is_char_boundary(cut) {
+                cut += 1;
+            }
+            line.drain(..cut);
+            self.len -= cut;
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    fn lines(scrollback: &TextScrollback) -> Vec<&str> {
+        scrollback.lines().collect()
+    }
+
+    #[test]
+    fn split_characters_and_lines_across_pushes() {
+        let mut scrollback = TextScrollback::new(10, 1024);
+        // '한' = ED 95 9C, and a \r\n split between pushes
+        for chunk in [&b"a\xED"[..], b"\x95\x9Cb\r", b"\nc"] {
+            scrollback.push(chunk);
+        }
+        assert_eq!(lines(&scrollback), ["a한b", "c"]);
+        assert_eq!(scrollback.byte_len(), 6);
+        assert_eq!(scrollback.snapshot(), "a한b\nc");
+
+        scrollback.push_str("\n");
+        assert_eq!(scrollback.len(), 2);
+        assert_eq!(scrollback.snapshot(), "a한b\nc\n");
+    }
+
+    #[test]
+    fn evicts_whole_lines_by_count_and_bytes() {
+        let mut scrollback = TextScrollback::new(3, 1024);
+        scrollback.push_str("1\n2\n3\n4\n5");
+        assert_eq!(lines(&scrollback), ["3", "4", "5"]);
+        assert_eq!(scrollback.evicted_lines(), 2);
+
+        let mut scrollback = TextScrollback::new(100, 8);
+        scrollback.push_str("한국\n어\nab");
+        // "한국" (6 bytes) no longer fits next to "어" and "ab"
+        assert_eq!(lines(&scrollback), ["어", "ab"]);
+        assert_eq!(scrollback.byte_len(), 5);
+        assert_eq!(scrollback.evicted_lines(), 1);
+        assert_eq!(scrollback.lines().next_back(), Some("ab"));
+    }
+
+    #[test]
+    fn long_line_is_shortened_at_character_boundary() {
+        let mut scrollback = TextScrollback::new(10, 4);
+        scrollback.push_str("a한국");
+        // Dropping 3 bytes would split '한', so all of "a한" goes
+        assert_eq!(lines(&scrollback), ["국"]);
+        assert_eq!(scrollback.byte_len(), 3);
+        assert_eq!(scrollback.evicted_lines(), 0);
+
+        scrollback.push_str("\nxyz");
+        assert_eq!(lines(&scrollback), ["xyz"]);
+        assert_eq!(scrollback.evicted_lines(), 1);
+    }
+
+    #[test]
+    fn flush_and_clear() {
+        let mut scrollback = TextScrollback::new(10, 1024);
+        scrollback.push(b"ok\xED\x95");
+        scrollback.flush();
+        assert_eq!(scrollback.snapshot(), "ok\u{FFFD}");
+
+        scrollback.push(b"\xED");
+        scrollback.clear();
+        assert!(scrollback.is_empty());
+        scrollback.push(b"a");
+        assert_eq!(scrollback.snapshot(), "a");
+    }
+}
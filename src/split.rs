@@ -0,0 +1,413 @@
+//! Encoder-side splitting of text into length-limited pieces.
+//!
+//! Message brokers, SMS segments and syslog impose byte limits, and slicing a
+//! `&str` at a fixed byte offset either panics or, on raw bytes, cuts a
+//! multi-byte character in half. [`Utf8Splitter`] always cuts at a character
+//! boundary and can optionally keep grapheme clusters together or prefer to
+//! break after whitespace. Limits can be measured in UTF-8 bytes or, for
+//! JavaScript and Java consumers, in UTF-16 code units.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+#[cfg(feature = "segmentation")]
+use unicode_segmentation::GraphemeCursor;
+
+/// The unit in which a [`Utf8Splitter`] measures its limit.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SplitUnit {
+    /// UTF-8 bytes, i.e. `str::len`.
+    #[default]
+    Utf8Bytes,
+    /// UTF-16 code units, i.e. JavaScript `String.length` or Java
+    /// `String.length()`. Characters outside the BMP count as two.
+    Utf16,
+}
+
+/// Lengths of a piece produced by a [`Utf8Splitter`], in every unit.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PieceStats {
+    /// Length in UTF-8 bytes.
+    pub bytes: usize,
+    /// Length in UTF-16 code units.
+    pub utf16_len: usize,
+    /// Number of Unicode scalar values (code points).
+    pub chars: usize,
+}
+
+impl PieceStats {
+    /// Measures `text`.
+    pub fn of(text: &str) -> Self {
+        let (utf16_len, chars) = text.chars().fold((0, 0), |(units, chars), c| {
+            (units + c.len_utf16(), chars + 1)
+        });
+        Self {
+            bytes: text.len(),
+            utf16_len,
+            chars,
+        }
+    }
+}
+
+/// The boundary rules used to choose where a piece ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Rules {
+    max: usize,
+    unit: SplitUnit,
+    #[cfg(feature = "segmentation")]
+    graphemes: bool,
+    prefer_whitespace: bool,
+}
+
+impl Rules {
+    /// Returns the largest character boundary in `text` within the limit, or
+    /// `None` if all of `text` fits.
+    fn limit_boundary(&self, text: &str) -> Option<usize> {
+        match self.unit {
+            SplitUnit::Utf8Bytes => {
+                if text.len() <= self.max {
+                    return None;
+                }
+                let mut cut = self.max;
+                while !text.is_char_boundary(cut) {
+                    cut -= 1;
+                }
+                Some(cut)
+            }
+            SplitUnit::Utf16 => {
+                let mut units = 0;
+                for (i, c) in text.char_indices() {
+                    units += c.len_utf16();
+                    if units > self.max {
+                        return Some(i);
+                    }
+                }
+                None
+            }
+        }
+    }
+
+    /// Returns the length of the next piece of `text`, or `None` if all of
+    /// `text` fits in one piece.
+    fn cut(&self, text: &str) -> Option<usize> {
+        let mut cut = self.limit_boundary(text)?;
+        if cut == 0 {
+            // A single character longer than the limit is emitted on its own
+            return text.chars().next().map(char::len_utf8);
+        }
+
+        #[cfg(feature = "segmentation")]
+        if self.graphemes {
+            // A grapheme cluster longer than the limit falls back to the
+            // character boundary found above
+            let boundary = grapheme_boundary_before(text, cut);
+            if boundary > 0 {
+                cut = boundary;
+            }
+        }
+
+        if self.prefer_whitespace && !text[cut..].starts_with(char::is_whitespace) {
+            if let Some((i, c)) = text[..cut].char_indices().rfind(|(_, c)| c.is_whitespace()) {
+                let after = i + c.len_utf8();
+                if self.is_boundary(text, after) {
+                    cut = after;
+                }
+            }
+        }
+        Some(cut)
+    }
+
+    #[cfg(feature = "segmentation")]
+    fn is_boundary(&self, text: &str, at: usize) -> bool {
+        !self.graphemes || grapheme_boundary_before(text, at) == at
+    }
+
+    #[cfg(not(feature = "segmentation"))]
+    fn is_boundary(&self, _text: &str, _at: usize) -> bool {
+        true
+    }
+}
+
+/// Returns the last extended grapheme cluster boundary at or before `at`.
+#[cfg(feature = "segmentation")]
+fn grapheme_boundary_before(text: &str, at: usize) -> usize {
+    let mut cursor = GraphemeCursor::new(at, text.len(), true);
+    if cursor.is_boundary(text, 0).unwrap_or(true) {
+        return at;
+    }
+    cursor.prev_boundary(text, 0).ok().flatten().unwrap_or(0)
+}
+
+/// Splits text into pieces of at most `max` UTF-8 bytes or UTF-16 code units.
+///
+/// A piece never ends in the middle of a code point, so a UTF-16 surrogate
+/// pair is never split either. A single character that is longer than the
+/// limit on its own (only possible when `max` is less than 4 bytes or 2 code
+/// units) is emitted as an oversized piece rather than broken.
+///
+/// [`split`](Self::split) borrows pieces of a single string. For text that
+/// arrives over time, [`push`](Self::push) and [`flush`](Self::flush) split
+/// the concatenation of everything pushed, so the pieces do not depend on how
+/// the text was divided between calls.
+///
+/// # Examples
+///
+/// ```
+/// use utf8_chunked::{SplitUnit, Utf8Splitter};
+///
+/// // '한' is 3 bytes, so a 4-byte limit fits one syllable per piece
+/// let splitter = Utf8Splitter::new(4);
+/// let pieces: Vec<&str> = splitter.split("한국어").collect();
+/// assert_eq!(pieces, ["한", "국", "어"]);
+///
+/// let splitter = Utf8Splitter::new(12).prefer_whitespace(true);
+/// let pieces: Vec<&str> = splitter.split("hello world again").collect();
+/// assert_eq!(pieces, ["hello world ", "again"]);
+///
+/// // '🦀' is one code point but two UTF-16 code units
+/// let splitter = Utf8Splitter::with_unit(3, SplitUnit::Utf16);
+/// let mut pieces = splitter.split("a🦀b🦀").with_stats();
+/// let (piece, stats) = pieces.next().unwrap();
+/// assert_eq!(piece, "a🦀");
+/// assert_eq!((stats.bytes, stats.utf16_len, stats.chars), (5, 3, 2));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Utf8Splitter {
+    rules: Rules,
+    /// Text pushed but not yet emitted, always within the limit.
+    pending: String,
+}
+
+impl Utf8Splitter {
+    /// Creates a new `Utf8Splitter` producing pieces of at most `max_bytes`
+    /// UTF-8 bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_bytes` is 0.
+    pub fn new(max_bytes: usize) -> Self {
+        Self::with_unit(max_bytes, SplitUnit::Utf8Bytes)
+    }
+
+    /// Creates a new `Utf8Splitter` producing pieces of at most `max`
+    /// of the given unit.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max` is 0.
+    pub fn with_unit(max: usize, unit: SplitUnit) -> Self {
+        assert!(max > 0, "max must be at least 1");
+        Self {
+            rules: Rules {
+                max,
+                unit,
+                #[cfg(feature = "segmentation")]
+                graphemes: false,
+                prefer_whitespace: false,
+            },
+            pending: String::new(),
+        }
+    }
+
+    /// Avoids breaking extended grapheme clusters, such as a letter followed by
+    /// a combining accent or a flag emoji (requires the `segmentation` feature).
+    ///
+    /// A cluster longer than the limit is still broken between code points.
+    #[cfg(feature = "segmentation")]
+    pub fn graphemes(mut self, enabled: bool) -> Self {
+        self.rules.graphemes = enabled;
+        self
+    }
+
+    /// Prefers to end a piece after the last whitespace character that fits,
+    /// falling back to the normal boundary for words longer than the limit.
+    ///
+    /// The whitespace stays at the end of the piece, so concatenating the
+    /// pieces always gives back the original text.
+    pub fn prefer_whitespace(mut self, enabled: bool) -> Self {
+        self.rules.prefer_whitespace = enabled;
+        self
+    }
+
+    /// Returns the maximum piece length, measured in [`unit`](Self::unit).
+    #[inline]
+    pub fn max(&self) -> usize {
+        self.rules.max
+    }
+
+    /// Returns the unit in which the limit is measured.
+    #[inline]
+    pub fn unit(&self) -> SplitUnit {
+        self.rules.unit
+    }
+
+    /// Returns an iterator over the pieces of `text`.
+    pub fn split<'a>(&self, text: &'a str) -> Utf8Split<'a> {
+        Utf8Split {
+            rules: self.rules,
+            rest: text,
+        }
+    }
+
+    /// Appends `text` and returns every piece that can no longer change.
+    ///
+    /// The last piece is held back until more text arrives or
+    /// [`flush`](Self::flush) is called, because its end depends on what
+    /// follows.
+    pub fn push(&mut self, text: &str) -> Vec<String> {
+        self.pending.push_str(text);
+
+        let mut pieces = Vec::new();
+        let mut start = 0;
+        while let Some(len) = self.rules.cut(&self.pending[start..]) {
+            pieces.push(String::from(&self.pending[start..start + len]));
+            start += len;
+        }
+        self.pending.drain(..start);
+        pieces
+    }
+
+    /// Returns the held-back piece at the end of the stream.
+    pub fn flush(&mut self) -> Option<String> {
+        if self.pending.is_empty() {
+            None
+        } else {
+            Some(core::mem::take(&mut self.pending))
+        }
+    }
+
+    /// Returns `true` if no text is held back.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+/// Iterator over the pieces of a string, created by [`Utf8Splitter::split`].
+#[derive(Debug, Clone)]
+pub struct Utf8Split<'a> {
+    rules: Rules,
+    rest: &'a str,
+}
+
+impl<'a> Iterator for Utf8Split<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        let len = self.rules.cut(self.rest).unwrap_or(self.rest.len());
+        let (piece, rest) = self.rest.split_at(len);
+        self.rest = rest;
+        Some(piece)
+    }
+}
+
+impl core::iter::FusedIterator for Utf8Split<'_> {}
+
+impl<'a> Utf8Split<'a> {
+    /// Pairs each piece with its [`PieceStats`].
+    pub fn with_stats(self) -> impl Iterator<Item = (&'a str, PieceStats)> {
+        self.map(|piece| (piece, PieceStats::of(piece)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pieces(splitter: &Utf8Splitter, text: &str) -> Vec<String> {
+        splitter.split(text).map(String::from).collect()
+    }
+
+    #[test]
+    fn never_breaks_code_points() {
+        let text = "a한🦀bé국";
+        for max in 1..=text.len() {
+            let splitter = Utf8Splitter::new(max);
+            let out = pieces(&splitter, text);
+            assert_eq!(out.concat(), text);
+            for piece in &out {
+                assert!(piece.len() <= max || piece.chars().count() == 1);
+            }
+        }
+    }
+
+    #[test]
+    fn prefers_whitespace() {
+        let splitter = Utf8Splitter::new(8).prefer_whitespace(true);
+        assert_eq!(
+            pieces(&splitter, "한 글 abcdefghij"),
+            ["한 글 ", "abcdefgh", "ij"]
+        );
+        // The next character is already whitespace: no need to back up
+        let splitter = Utf8Splitter::new(5).prefer_whitespace(true);
+        assert_eq!(pieces(&splitter, "ab cd efgh"), ["ab cd", " efgh"]);
+    }
+
+    #[cfg(feature = "segmentation")]
+    #[test]
+    fn keeps_grapheme_clusters() {
+        // "e" + U+0301 is 3 bytes; a flag is two 4-byte regional indicators
+        let text = "ae\u{301}\u{1F1F0}\u{1F1F7}";
+        let splitter = Utf8Splitter::new(6).graphemes(true);
+        assert_eq!(
+            pieces(&splitter, text),
+            ["ae\u{301}", "\u{1F1F0}", "\u{1F1F7}"]
+        );
+        let splitter = Utf8Splitter::new(8).graphemes(true);
+        assert_eq!(pieces(&splitter, text), ["ae\u{301}", "\u{1F1F0}\u{1F1F7}"]);
+        let splitter = Utf8Splitter::new(2).graphemes(true);
+        assert_eq!(pieces(&splitter, "e\u{301}"), ["e", "\u{301}"]);
+    }
+
+    #[test]
+    fn utf16_limit_never_splits_surrogate_pairs() {
+        let text = "a🦀한😀😀b";
+        for max in 1..=8 {
+            let splitter = Utf8Splitter::with_unit(max, SplitUnit::Utf16);
+            let out: Vec<_> = splitter.split(text).with_stats().collect();
+            assert_eq!(out.iter().map(|(p, _)| *p).collect::<String>(), text);
+            for (piece, stats) in out {
+                assert_eq!(stats, PieceStats::of(piece));
+                assert_eq!(stats.utf16_len, piece.encode_utf16().count());
+                assert!(stats.utf16_len <= max || stats.chars == 1);
+            }
+        }
+        let splitter = Utf8Splitter::with_unit(3, SplitUnit::Utf16);
+        assert_eq!(pieces(&splitter, text), ["a🦀", "한😀", "😀b"]);
+    }
+
+    #[test]
+    fn piece_stats() {
+        let stats = PieceStats::of("한🦀a");
+        assert_eq!(
+            stats,
+            PieceStats {
+                bytes: 8,
+                utf16_len: 4,
+                chars: 3
+            }
+        );
+    }
+
+    #[test]
+    fn push_matches_split_regardless_of_division() {
+        let text = "syslog 메시지 with 🦀 emoji and more text";
+        for splitter in [
+            Utf8Splitter::new(10).prefer_whitespace(true),
+            Utf8Splitter::with_unit(5, SplitUnit::Utf16),
+        ] {
+            let expected = pieces(&splitter, text);
+            for at in (0..=text.len()).filter(|&i| text.is_char_boundary(i)) {
+                let mut s = splitter.clone();
+                let mut out = s.push(&text[..at]);
+                out.extend(s.push(&text[at..]));
+                out.extend(s.flush());
+                assert_eq!(out, expected, "at={at}");
+                assert!(s.is_empty());
+            }
+        }
+    }
+}
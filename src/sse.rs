@@ -0,0 +1,336 @@
+//! Server-Sent Events (SSE) parsing on top of [`Utf8Chunker`].
+//!
+//! Implements the `text/event-stream` parsing rules from the WHATWG HTML
+//! specification: `event:`, `data:`, `id:` and `retry:` fields, comments,
+//! multi-line data, and all three line endings (`\r\n`, `\n`, `\r`), with
+//! arbitrary byte splits anywhere in the stream.
+
+use alloc::collections::VecDeque;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::mem;
+
+use crate::Utf8Chunker;
+
+/// A single dispatched Server-Sent Event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SseEvent {
+    /// The event type, or `"message"` if no `event:` field was given.
+    pub event: String,
+    /// The event data. Multiple `data:` lines are joined with `\n`.
+    pub data: String,
+    /// The last event ID seen on the stream, if any.
+    ///
+    /// As the specification requires, an `id:` field persists for all following
+    /// events until it is changed.
+    pub id: Option<String>,
+    /// The last reconnection time in milliseconds set on the stream, if any.
+    ///
+    /// Like `id`, a `retry:` field applies to the whole stream, so it is kept
+    /// for all following events, including one sent in a block without data.
+    pub retry: Option<u64>,
+}
+
+/// Incremental Server-Sent Events parser.
+///
+/// Bytes are decoded with [`Utf8Chunker`], so a multi-byte character split
+/// across chunks never corrupts the event data.
+///
+/// # Examples
+///
+/// ```
+/// use utf8_chunked::SseDecoder;
+///
+/// let mut decoder = SseDecoder::new();
+///
+/// // '한' = ED 95 9C, split across two chunks
+/// assert!(decoder.push(b"event: greeting\ndata: \xED\x95").is_empty());
+/// let events = decoder.push(b"\x9C\n\n");
+///
+/// assert_eq!(events.len(), 1);
+/// assert_eq!(events[0].event, "greeting");
+/// assert_eq!(events[0].data, "한");
+/// ```
+#[derive(Debug, Default)]
+pub struct SseDecoder {
+    chunker: Utf8Chunker,
+    /// Text of the line currently being received.
+    line: String,
+    /// The previous chunk ended with `\r`; skip a leading `\n` in the next one.
+    pending_cr: bool,
+    /// Whether any text has been seen yet (for stripping a leading BOM).
+    started: bool,
+    event: String,
+    data: String,
+    has_data: bool,
+    last_event_id: String,
+    retry: Option<u64>,
+    events: VecDeque<SseEvent>,
+}
+
+impl SseDecoder {
+    /// Creates a new `SseDecoder`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Processes an incoming byte chunk and returns all events it completed.
+    pub fn push(&mut self, data: &[u8]) -> Vec<SseEvent> {
+        self.feed(data);
+        self.events.drain(..).collect()
+    }
+
+    /// Returns the last event ID seen on the stream.
+    ///
+    /// This is the value to send in a `Last-Event-ID` header when reconnecting.
+    #[inline]
+    pub fn last_event_id(&self) -> &str {
+        &self.last_event_id
+    }
+
+    /// Returns the last reconnection time in milliseconds set by a `retry:`
+    /// field, if any.
+    ///
+    /// A block containing only a `retry:` field dispatches no event, so this
+    /// is how a client sees it.
+    #[inline]
+    pub fn retry(&self) -> Option<u64> {
+        self.retry
+    }
+
+    fn feed(&mut self, data: &[u8]) {
+        if let Some(text) = self.chunker.push(data) {
+            self.feed_text(&text);
+        }
+    }
+
+    fn feed_text(&mut self, text: &str) {
+        let mut rest = text;
+
+        if !self.started {
+            self.started = true;
+            rest = rest.strip_prefix('\u{FEFF}').unwrap_or(rest);
+        }
+        if mem::take(&mut self.pending_cr) {
+            rest = rest.strip_prefix('\n').unwrap_or(rest);
+        }
+
+        while let Some(pos) = rest.find(['\r', '\n']) {
+            self.line.push_str(&rest[..pos]);
+            let is_cr = rest.as_bytes()[pos] == b'\r';
+            rest = &rest[pos + 1..];
+
+            if is_cr {
+                if rest.is_empty() {
+                    // A `\n` may still arrive at the start of the next chunk
+                    self.pending_cr = true;
+                } else {
+                    rest = rest.strip_prefix('\n').unwrap_or(rest);
+                }
+            }
+
+            let line = mem::take(&mut self.line);
+            self.process_line(&line);
+        }
+
+        self.line.push_str(rest);
+    }
+
+    fn process_line(&mut self, line: &str) {
+        if line.is_empty() {
+            self.dispatch();
+            return;
+        }
+        if line.starts_with(':') {
+            // Comment
+            return;
+        }
+
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+
+        match field {
+            "event" => {
+                self.event.clear();
+                self.event.push_str(value);
+            }
+            "data" => {
+                if self.has_data {
+                    self.data.push('\n');
+                }
+                self.data.push_str(value);
+                self.has_data = true;
+            }
+            "id" if !value.contains('\0') => {
+                self.last_event_id.clear();
+                self.last_event_id.push_str(value);
+            }
+            "retry" if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) => {
+                self.retry = value.parse().ok();
+            }
+            _ => {}
+        }
+    }
+
+    fn dispatch(&mut self) {
+        let event = mem::take(&mut self.event);
+
+        if !mem::take(&mut self.has_data) {
+            return;
+        }
+
+        self.events.push_back(SseEvent {
+            event: if event.is_empty() {
+                String::from("message")
+            } else {
+                event
+            },
+            data: mem::take(&mut self.data),
+            id: if self.last_event_id.is_empty() {
+                None
+            } else {
+                Some(self.last_event_id.clone())
+            },
+            retry: self.retry,
+        });
+    }
+}
+
+#[cfg(feature = "tokio")]
+mod codec {
+    use super::{SseDecoder, SseEvent};
+    use std::io;
+    use tokio_util::bytes::BytesMut;
+    use tokio_util::codec::Decoder;
+
+    /// Decodes a byte stream into [`SseEvent`]s.
+    ///
+    /// An event that has not been terminated by a blank line when the stream
+    /// ends is discarded, as the specification requires.
+    impl Decoder for SseDecoder {
+        type Item = SseEvent;
+        type Error = io::Error;
+
+        fn decode(&mut self, buf: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+            if self.events.is_empty() && !buf.is_empty() {
+                let data = buf.split_to(buf.len());
+                self.feed(&data);
+            }
+            Ok(self.events.pop_front())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(chunks: &[&[u8]]) -> Vec<SseEvent> {
+        let mut decoder = SseDecoder::new();
+        chunks.iter().flat_map(|c| decoder.push(c)).collect()
+    }
+
+    #[test]
+    fn single_event() {
+        let events = collect(&[b"data: hello\n\n"]);
+        assert_eq!(
+            events,
+            [SseEvent {
+                event: "message".into(),
+                data: "hello".into(),
+                id: None,
+                retry: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn all_fields() {
+        let events = collect(&[b"event: update\nid: 7\nretry: 3000\ndata: x\n\n"]);
+        assert_eq!(events[0].event, "update");
+        assert_eq!(events[0].id.as_deref(), Some("7"));
+        assert_eq!(events[0].retry, Some(3000));
+        assert_eq!(events[0].data, "x");
+    }
+
+    #[test]
+    fn multi_line_data() {
+        let events = collect(&[b"data: first\ndata:second\ndata\n\n"]);
+        assert_eq!(events[0].data, "first\nsecond\n");
+    }
+
+    #[test]
+    fn comments_and_unknown_fields_ignored() {
+        let events = collect(&[b": keep-alive\nfoo: bar\ndata: ok\n\n"]);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].data, "ok");
+    }
+
+    #[test]
+    fn blank_line_without_data_does_not_dispatch() {
+        let events = collect(&[b"event: ping\n\ndata: x\n\n"]);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event, "message");
+    }
+
+    #[test]
+    fn id_persists_across_events() {
+        let events = collect(&[b"id: 1\ndata: a\n\ndata: b\n\nid\ndata: c\n\n"]);
+        assert_eq!(events[0].id.as_deref(), Some("1"));
+        assert_eq!(events[1].id.as_deref(), Some("1"));
+        assert_eq!(events[2].id, None);
+    }
+
+    #[test]
+    fn invalid_retry_ignored() {
+        let events = collect(&[b"retry: 10s\ndata: x\n\n"]);
+        assert_eq!(events[0].retry, None);
+    }
+
+    #[test]
+    fn retry_without_data_is_kept() {
+        let mut decoder = SseDecoder::new();
+        assert!(decoder.push(b"retry: 5000\n\n").is_empty());
+        assert_eq!(decoder.retry(), Some(5000));
+
+        let events = decoder.push(b"data: x\n\ndata: y\n\n");
+        assert_eq!(events[0].retry, Some(5000));
+        assert_eq!(events[1].retry, Some(5000));
+        assert_eq!(decoder.retry(), Some(5000));
+    }
+
+    #[test]
+    fn crlf_split_between_chunks() {
+        let events = collect(&[b"data: a\r", b"\n\r", b"\ndata: b\r\r"]);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].data, "a");
+        assert_eq!(events[1].data, "b");
+    }
+
+    #[test]
+    fn leading_bom_stripped() {
+        let events = collect(&[b"\xEF\xBB", b"\xBFdata: x\n\n"]);
+        assert_eq!(events[0].data, "x");
+    }
+
+    #[test]
+    fn every_byte_split() {
+        let input = "event: 인사\nid: 🦀\ndata: 안녕하세요\ndata: 世界 🌍\n\n".as_bytes();
+        let mut decoder = SseDecoder::new();
+        let events: Vec<SseEvent> = input.iter().flat_map(|&b| decoder.push(&[b])).collect();
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event, "인사");
+        assert_eq!(events[0].id.as_deref(), Some("🦀"));
+        assert_eq!(events[0].data, "안녕하세요\n世界 🌍");
+        assert_eq!(decoder.last_event_id(), "🦀");
+    }
+
+    #[test]
+    fn incomplete_event_not_dispatched() {
+        let events = collect(&[b"data: partial\n"]);
+        assert!(events.is_empty());
+    }
+}
@@ -0,0 +1,411 @@
+//! Display-width-aware chunking for terminal rendering (requires the `width`
+//! feature).
+//!
+//! Column widths follow Unicode Standard Annex #11 (East Asian Width) via the
+//! `unicode-width` crate, so '한' and most emoji take two columns.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::mem;
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::Utf8Chunker;
+
+/// A piece of decoded text that fits within one row of a fixed-width pane.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WidthPiece {
+    /// The text, without any line terminator.
+    pub text: String,
+    /// Display width of `text` in terminal columns.
+    pub width: usize,
+    /// Column of the row at which `text` starts.
+    pub column: usize,
+    /// `true` if the row is complete after this piece, because it is full, a
+    /// `\n` followed, or the next character did not fit.
+    ///
+    /// When that `\n` or character arrives in a later push than the rest of
+    /// a row that is not full, the row is closed by a piece with empty `text`.
+    pub ends_row: bool,
+}
+
+/// Longest trailing cluster, in bytes, that is re-measured as extenders
+/// arrive; longer runs of combining marks add no columns.
+const MAX_CLUSTER_LEN: usize = 64;
+
+/// Incremental UTF-8 decoder that splits its output into rows of at most
+/// `max_width` terminal columns.
+///
+/// The current column is carried across pushes, so the pieces describe the
+/// same rows no matter where chunk boundaries fall. A wide character that does
+/// not fit at the end of a row moves to the next one, and a `\n` right after
+/// an exactly full row does not add an empty one. Control characters other
+/// than `\n` count as zero columns.
+///
+/// Zero-width characters that extend the previous one (combining marks, ZERO
+/// WIDTH JOINER, variation selectors) and the characters joined by a ZWJ stay
+/// on the row of the character they extend, and the whole cluster is measured
+/// at once: an emoji ZWJ sequence such as 👨‍👩‍👧 takes two columns, and
+/// "❤\u{FE0F}" takes two where a bare '❤' takes one. If an extender makes
+/// the cluster too wide for its row, the cluster moves to the next row.
+///
+/// Because such a character may still follow, a row that is exactly full is
+/// held back until the next character or `\n` arrives, or until
+/// [`flush`](Self::flush).
+///
+/// # Examples
+///
+/// ```
+/// use utf8_chunked::WidthChunker;
+///
+/// let mut chunker = WidthChunker::new(5);
+///
+/// // "ab한글" is 6 columns wide: '글' wraps to the next row
+/// let pieces = chunker.push("ab한글".as_bytes());
+/// assert_eq!((pieces[0].text.as_str(), pieces[0].width, pieces[0].ends_row), ("ab한", 4, true));
+/// assert_eq!((pieces[1].text.as_str(), pieces[1].width, pieces[1].ends_row), ("글", 2, false));
+/// assert_eq!(chunker.column(), 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct WidthChunker {
+    chunker: Utf8Chunker,
+    max_width: usize,
+    ambiguous_wide: bool,
+    /// Columns used on the current row.
+    column: usize,
+    /// The current row, exactly full and not yet emitted.
+    held: Option<WidthPiece>,
+    /// The last character placed on the current row and its extenders.
+    cluster: String,
+    /// Column at which `cluster` starts.
+    cluster_column: usize,
+    /// Display width of `cluster`.
+    cluster_width: usize,
+}
+
+impl WidthChunker {
+    /// Creates a new `WidthChunker` for rows `max_width` columns wide.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_width` is 0.
+    pub fn new(max_width: usize) -> Self {
+        assert!(max_width > 0, "max_width must be at least 1");
+        Self {
+            chunker: Utf8Chunker::new(),
+            max_width,
+            ambiguous_wide: false,
+            column: 0,
+            held: None,
+            cluster: String::new(),
+            cluster_column: 0,
+            cluster_width: 0,
+        }
+    }
+
+    /// Treats East Asian Ambiguous characters (such as '±' or '→') as two
+    /// columns wide, as CJK terminals usually do.
+    pub fn ambiguous_wide(mut self, wide: bool) -> Self {
+        self.ambiguous_wide = wide;
+        self
+    }
+
+    /// Processes an incoming byte chunk and returns the row pieces it produced.
+    pub fn push(&mut self, data: &[u8]) -> Vec<WidthPiece> {
+        match self.chunker.push(data) {
+            Some(text) => self.split(&text),
+            None => Vec::new(),
+        }
+    }
+
+    /// Flushes any buffered bytes and a held-back full row at the end of the
+    /// stream.
+    ///
+    /// An incomplete multi-byte sequence is replaced with U+FFFD.
+    pub fn flush(&mut self) -> Vec<WidthPiece> {
+        let mut pieces = match self.chunker.flush() {
+            Some(text) => self.split(&text),
+            None => Vec::new(),
+        };
+        if let Some(row) = self.held.take() {
+            pieces.push(row);
+            self.column = 0;
+            self.cluster.clear();
+        }
+        pieces
+    }
+
+    /// Returns the number of columns used on the current row.
+    ///
+    /// This is `max_width` or more while an exactly full row is held back.
+    #[inline]
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Returns the display width of `c` under this chunker's settings.
+    pub fn char_width(&self, c: char) -> usize {
+        let width = if self.ambiguous_wide {
+            c.width_cjk()
+        } else {
+            c.width()
+        };
+        width.unwrap_or(0)
+    }
+
+    fn str_width(&self, s: &str) -> usize {
+        if self.ambiguous_wide {
+            s.width_cjk()
+        } else {
+            s.width()
+        }
+    }
+
+    /// Returns `true` if `c` belongs to the cluster of the previous character.
+    fn extends_cluster(&self, c: char) -> bool {
+        !self.cluster.is_empty()
+            && (self.cluster.ends_with('\u{200D}')
+                || ('\u{1F3FB}'..='\u{1F3FF}').contains(&c)
+                || (self.char_width(c) == 0 && !c.is_control()))
+    }
+
+    fn split(&mut self, text: &str) -> Vec<WidthPiece> {
+        let mut pieces = Vec::new();
+        let (mut current, mut width, mut start) = match self.held.take() {
+            Some(row) => (row.text, row.width, row.column),
+            None => (String::new(), 0, self.column),
+        };
+
+        for c in text.chars() {
+            if c == '\n' {
+                pieces.push(WidthPiece {
+                    text: mem::take(&mut current),
+                    width: mem::take(&mut width),
+                    column: start,
+                    ends_row: true,
+                });
+                self.column = 0;
+                start = 0;
+                self.cluster.clear();
+                continue;
+            }
+
+            if self.extends_cluster(c) {
+                if self.cluster.len() >= MAX_CLUSTER_LEN {
+                    current.push(c);
+                    continue;
+                }
+                let in_current = self.cluster.len() <= current.len();
+                self.cluster.push(c);
+                let cluster_width = self.str_width(&self.cluster);
+                let column = self.cluster_column + cluster_width;
+                if column <= self.max_width || self.cluster_column == 0 {
+                    current.push(c);
+                    width = (width + cluster_width).saturating_sub(self.cluster_width);
+                    self.column = column;
+                    self.cluster_width = cluster_width;
+                    continue;
+                }
+                if in_current {
+                    // Move the whole cluster to the next row
+                    let at = current.len() - (self.cluster.len() - c.len_utf8());
+                    let moved = current.split_off(at);
+                    pieces.push(WidthPiece {
+                        text: mem::replace(&mut current, moved),
+                        width: self.cluster_column - start,
+                        column: start,
+                        ends_row: true,
+                    });
+                    current.push(c);
+                    width = cluster_width;
+                    start = 0;
+                    self.column = cluster_width;
+                    self.cluster_column = 0;
+                    self.cluster_width = cluster_width;
+                    continue;
+                }
+                // Part of the cluster was already emitted: place `c` on its own
+                self.cluster.pop();
+            }
+
+            let w = self.char_width(c);
+            if self.column > 0 && self.column + w > self.max_width {
+                pieces.push(WidthPiece {
+                    text: mem::take(&mut current),
+                    width: mem::take(&mut width),
+                    column: start,
+                    ends_row: true,
+                });
+                self.column = 0;
+                start = 0;
+            }
+
+            current.push(c);
+            width += w;
+            self.cluster.clear();
+            self.cluster.push(c);
+            self.cluster_column = self.column;
+            self.cluster_width = w;
+            self.column += w;
+        }
+
+        if self.column >= self.max_width {
+            // An extender in the next push may still belong on this row
+            self.held = Some(WidthPiece {
+                text: current,
+                width,
+                column: start,
+                ends_row: true,
+            });
+        } else if !current.is_empty() {
+            pieces.push(WidthPiece {
+                text: current,
+                width,
+                column: start,
+                ends_row: false,
+            });
+        }
+        pieces
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feeds `input` in `chunk`-byte pieces and reassembles complete rows.
+    fn rows(max_width: usize, input: &str, chunk: usize) -> Vec<(String, usize)> {
+        let mut c = WidthChunker::new(max_width);
+        let mut rows = Vec::new();
+        let mut row = (String::new(), 0);
+        let mut pieces: Vec<WidthPiece> = input
+            .as_bytes()
+            .chunks(chunk)
+            .flat_map(|b| c.push(b))
+            .collect();
+        pieces.extend(c.flush());
+        for piece in pieces {
+            assert_eq!(piece.column, row.1);
+            // An empty piece only closes a row that is not full
+            assert!(!piece.text.is_empty() || (piece.ends_row && piece.column < max_width));
+            row.0.push_str(&piece.text);
+            row.1 += piece.width;
+            if piece.ends_row {
+                rows.push(mem::take(&mut row));
+            }
+        }
+        if !row.0.is_empty() {
+            rows.push(row);
+        }
+        rows
+    }
+
+    #[test]
+    fn wrapping_is_independent_of_chunk_boundaries() {
+        let input = "한국어 🦀 mixed text\n\n世界é\u{301}!";
+        let expected = rows(6, input, input.len());
+        assert_eq!(
+            expected,
+            [
+                ("한국어".into(), 6),
+                (" 🦀 mi".into(), 6),
+                ("xed te".into(), 6),
+                ("xt".into(), 2),
+                ("".into(), 0),
+                ("世界é\u{301}!".into(), 6),
+            ]
+        );
+        for chunk in 1..input.len() {
+            assert_eq!(rows(6, input, chunk), expected, "chunk={chunk}");
+        }
+    }
+
+    #[test]
+    fn exactly_full_row_then_newline() {
+        assert_eq!(rows(2, "한\n", 1), [("한".into(), 2)]);
+        assert_eq!(rows(2, "한\n\n", 1), [("한".into(), 2), ("".into(), 0)]);
+    }
+
+    #[test]
+    fn full_row_is_held_until_the_next_character() {
+        let piece = |text: &str, width, column, ends_row| WidthPiece {
+            text: text.into(),
+            width,
+            column,
+            ends_row,
+        };
+        let mut c = WidthChunker::new(2);
+        assert_eq!(c.push("한".as_bytes()), []);
+        assert_eq!(c.column(), 2);
+        assert_eq!(c.push("한".as_bytes()), [piece("한", 2, 0, true)]);
+        assert_eq!(c.push(b"\n"), [piece("한", 2, 0, true)]);
+        assert_eq!(c.push(b"a"), [piece("a", 1, 0, false)]);
+        assert_eq!(c.push(b"b\n"), [piece("b", 1, 1, true)]);
+        assert_eq!(c.column(), 0);
+
+        assert_eq!(c.push("한".as_bytes()), []);
+        assert_eq!(c.flush(), [piece("한", 2, 0, true)]);
+        assert_eq!(c.column(), 0);
+    }
+
+    /// Checks `rows` for every chunk size.
+    fn assert_rows(max_width: usize, input: &str, expected: &[(&str, usize)]) {
+        let expected: Vec<(String, usize)> =
+            expected.iter().map(|&(text, w)| (text.into(), w)).collect();
+        for chunk in 1..=input.len() {
+            assert_eq!(rows(max_width, input, chunk), expected, "chunk={chunk}");
+        }
+    }
+
+    #[test]
+    fn zwj_sequence_stays_on_one_row() {
+        let family = "👨\u{200D}👩\u{200D}👧";
+        assert_rows(2, &alloc::format!("{family}x"), &[(family, 2), ("x", 1)]);
+        assert_rows(2, &alloc::format!("a{family}"), &[("a", 1), (family, 2)]);
+        assert_rows(
+            4,
+            &alloc::format!("{family}ab"),
+            &[(&alloc::format!("{family}ab"), 4)],
+        );
+    }
+
+    #[test]
+    fn combining_mark_at_row_edge_stays_with_its_base() {
+        assert_rows(3, "abe\u{301}", &[("abe\u{301}", 3)]);
+        assert_rows(3, "abe\u{301}c", &[("abe\u{301}", 3), ("c", 1)]);
+        assert_rows(1, "e\u{301}\u{302}x", &[("e\u{301}\u{302}", 1), ("x", 1)]);
+    }
+
+    #[test]
+    fn variation_selector_widens_emoji() {
+        let heart = "\u{2764}\u{FE0F}";
+        assert_rows(2, heart, &[(heart, 2)]);
+        // The bare heart fits the last column, the emoji form does not
+        assert_rows(
+            3,
+            &alloc::format!("ab{heart}c"),
+            &[("ab", 2), (&alloc::format!("{heart}c"), 3)],
+        );
+        assert_rows(
+            4,
+            &alloc::format!("{heart}{heart}"),
+            &[(&alloc::format!("{heart}{heart}"), 4)],
+        );
+    }
+
+    #[test]
+    fn wide_char_in_narrow_pane() {
+        assert_eq!(
+            rows(1, "a한b", 1),
+            [("a".into(), 1), ("한".into(), 2), ("b".into(), 1)]
+        );
+    }
+
+    #[test]
+    fn ambiguous_width() {
+        let c = WidthChunker::new(10);
+        assert_eq!(c.char_width('→'), 1);
+        let c = c.ambiguous_wide(true);
+        assert_eq!(c.char_width('→'), 2);
+    }
+}
@@ -0,0 +1,186 @@
+//! Reassembly of fragmented WebSocket text messages (RFC 6455).
+//!
+//! A text message may be split across a text frame and any number of
+//! continuation frames, at any byte. The message as a whole must be valid
+//! UTF-8, and endpoints may fail the connection as soon as an invalid sequence
+//! is seen (RFC 6455 §8.1).
+
+use alloc::string::String;
+use core::fmt;
+
+use crate::{from_utf8, Utf8Chunker};
+
+/// Error returned by [`WsTextAssembler::push_frame`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WsTextError {
+    /// The payload contains a byte sequence that can never be valid UTF-8.
+    InvalidUtf8,
+    /// The final frame ended in the middle of a multi-byte character.
+    Truncated,
+}
+
+impl WsTextError {
+    /// Returns the close code to fail the connection with (1007, invalid
+    /// frame payload data).
+    #[inline]
+    pub fn close_code(&self) -> u16 {
+        1007
+    }
+}
+
+impl fmt::Display for WsTextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WsTextError::InvalidUtf8 => f.write_str("invalid UTF-8 in text message"),
+            WsTextError::Truncated => f.write_str("text message ends with an incomplete character"),
+        }
+    }
+}
+
+impl core::error::Error for WsTextError {}
+
+/// Incremental assembler for fragmented WebSocket text messages.
+///
+/// Feed it the (unmasked) payload of each text and continuation frame along
+/// with its FIN flag. Text is emitted as soon as it is complete, so long
+/// messages can be rendered while they are still arriving.
+///
+/// Unlike [`Utf8Chunker`], which drops invalid bytes, the assembler fails fast
+/// on the first sequence that can never become valid. After an error the
+/// assembler is reset and ready for the next message.
+///
+/// # Examples
+///
+/// ```
+/// use utf8_chunked::{WsTextAssembler, WsTextError};
+///
+/// let mut ws = WsTextAssembler::new();
+///
+/// // '한' = ED 95 9C, split across a text frame and a continuation frame
+/// assert_eq!(ws.push_frame(&[b'a', 0xED, 0x95], false), Ok(Some("a".to_string())));
+/// assert_eq!(ws.push_frame(&[0x9C], true), Ok(Some("한".to_string())));
+///
+/// // The final frame cuts a character short
+/// assert_eq!(ws.push_frame(&[0xED, 0x95], true), Err(WsTextError::Truncated));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct WsTextAssembler {
+    chunker: Utf8Chunker,
+}
+
+impl WsTextAssembler {
+    /// Creates a new `WsTextAssembler`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Processes one frame payload of a text message.
+    ///
+    /// `fin` is the frame's FIN bit. Returns any text completed by this frame,
+    /// or `None` if it was empty or only carried part of a character.
+    pub fn push_frame(&mut self, payload: &[u8], fin: bool) -> Result<Option<String>, WsTextError> {
+        let expected_len = self.chunker.buffered_len() + payload.len();
+        let text = self.chunker.push(payload);
+
+        // Every byte is either emitted as text or still buffered; anything
+        // missing was dropped as invalid. The buffer itself must also still be
+        // the prefix of a valid character.
+        let accounted_len = text.as_ref().map_or(0, String::len) + self.chunker.buffered_len();
+        let pending_valid = match from_utf8(self.chunker.pending_bytes()) {
+            Ok(_) => true,
+            Err(e) => e.error_len().is_none(),
+        };
+        if accounted_len != expected_len || !pending_valid {
+            self.chunker.reset();
+            return Err(WsTextError::InvalidUtf8);
+        }
+
+        if fin && !self.chunker.is_empty() {
+            self.chunker.reset();
+            return Err(WsTextError::Truncated);
+        }
+
+        Ok(text)
+    }
+
+    /// Returns `true` if no part of a character is carried into the next frame.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.chunker.is_empty()
+    }
+
+    /// Discards any carried bytes, e.g. when the connection is closed mid-message.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.chunker.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_frame_message() {
+        let mut ws = WsTextAssembler::new();
+        assert_eq!(
+            ws.push_frame("안녕".as_bytes(), true),
+            Ok(Some("안녕".into()))
+        );
+        assert!(ws.is_empty());
+    }
+
+    #[test]
+    fn emoji_split_across_three_frames() {
+        // '🦀' = F0 9F A6 80
+        let mut ws = WsTextAssembler::new();
+        assert_eq!(ws.push_frame(&[0xF0], false), Ok(None));
+        assert_eq!(ws.push_frame(&[0x9F, 0xA6], false), Ok(None));
+        assert_eq!(ws.push_frame(&[0x80], true), Ok(Some("🦀".into())));
+    }
+
+    #[test]
+    fn empty_frames_allowed() {
+        let mut ws = WsTextAssembler::new();
+        assert_eq!(ws.push_frame(b"", false), Ok(None));
+        assert_eq!(ws.push_frame(b"", true), Ok(None));
+    }
+
+    #[test]
+    fn invalid_byte_fails_fast() {
+        let mut ws = WsTextAssembler::new();
+        assert_eq!(
+            ws.push_frame(b"ok\xFFmore", false),
+            Err(WsTextError::InvalidUtf8)
+        );
+        assert!(ws.is_empty());
+    }
+
+    #[test]
+    fn invalid_prefix_fails_before_fin() {
+        // ED A0 starts a UTF-16 surrogate, which is never valid UTF-8
+        let mut ws = WsTextAssembler::new();
+        assert_eq!(
+            ws.push_frame(&[b'a', 0xED, 0xA0], false),
+            Err(WsTextError::InvalidUtf8)
+        );
+    }
+
+    #[test]
+    fn invalid_continuation_in_next_frame() {
+        let mut ws = WsTextAssembler::new();
+        assert_eq!(ws.push_frame(&[0xED], false), Ok(None));
+        assert_eq!(ws.push_frame(b"abc", true), Err(WsTextError::InvalidUtf8));
+    }
+
+    #[test]
+    fn truncated_at_fin() {
+        let mut ws = WsTextAssembler::new();
+        assert_eq!(
+            ws.push_frame(&[b'x', 0xF0, 0x9F], true),
+            Err(WsTextError::Truncated)
+        );
+        assert!(ws.is_empty());
+        assert_eq!(ws.push_frame(b"next", true), Ok(Some("next".into())));
+    }
+}
@@ -227,7 +227,7 @@ fn two_byte_latin_extended() {
 #[cfg(feature = "tokio")]
 mod tokio_tests {
     use tokio_stream::StreamExt;
-    use utf8_chunked::{utf8_safe_stream, Utf8Codec};
+    use utf8_chunked::{utf8_safe_stream, Utf8Bytes, Utf8BytesCodec, Utf8Codec};
 
     #[tokio::test]
     async fn utf8_safe_stream_basic() {
@@ -287,4 +287,410 @@ mod tokio_tests {
         }
         assert_eq!(result, "🦀 Rust");
     }
+
+    #[tokio::test]
+    async fn bytes_codec_with_framed_read() {
+        use tokio_util::codec::FramedRead;
+
+        // '한' = ED 95 9C, '🦀' = F0 9F A6 80
+        let chunks: Vec<Result<tokio_util::bytes::Bytes, std::io::Error>> = vec![
+            Ok(tokio_util::bytes::Bytes::from_static(&[b'H', b'i', 0xED, 0x95])),
+            Ok(tokio_util::bytes::Bytes::from_static(&[0x9C, b'!', 0xF0])),
+            Ok(tokio_util::bytes::Bytes::from_static(&[0x9F])),
+            Ok(tokio_util::bytes::Bytes::from_static(&[0xA6, 0x80, b'.'])),
+        ];
+
+        let stream = tokio_stream::iter(chunks);
+        let reader = tokio_util::io::StreamReader::new(stream);
+        let mut framed = FramedRead::new(reader, Utf8BytesCodec::new());
+
+        let mut result = String::new();
+        while let Some(text) = framed.next().await {
+            result.push_str(&text.unwrap());
+        }
+        assert_eq!(result, "Hi한!🦀.");
+    }
+
+    #[tokio::test]
+    async fn bytes_codec_flushes_incomplete_at_eof() {
+        use tokio_util::codec::FramedRead;
+
+        let chunks: Vec<Result<tokio_util::bytes::Bytes, std::io::Error>> = vec![Ok(
+            tokio_util::bytes::Bytes::from_static(&[b'o', b'k', 0xE4, 0xB8]),
+        )];
+
+        let stream = tokio_stream::iter(chunks);
+        let reader = tokio_util::io::StreamReader::new(stream);
+        let framed = FramedRead::new(reader, Utf8BytesCodec::new());
+        let items: Vec<Utf8Bytes> = framed.map(Result::unwrap).collect().await;
+
+        assert_eq!(items[0], "ok");
+        assert_eq!(items.last().unwrap(), "\u{FFFD}");
+    }
+
+    #[tokio::test]
+    async fn codecs_skip_invalid_bytes_mid_chunk() {
+        use tokio_util::codec::FramedRead;
+
+        let data: &'static [u8] = b"hi\xFFthere\x80!";
+        let reader = tokio_util::io::StreamReader::new(tokio_stream::once(Ok::<_, std::io::Error>(
+            tokio_util::bytes::Bytes::from_static(data),
+        )));
+        let framed = FramedRead::new(reader, Utf8Codec::new());
+        let text: String = framed.map(Result::unwrap).collect().await;
+        assert_eq!(text, "hithere!");
+
+        let reader = tokio_util::io::StreamReader::new(tokio_stream::once(Ok::<_, std::io::Error>(
+            tokio_util::bytes::Bytes::from_static(data),
+        )));
+        let framed = FramedRead::new(reader, Utf8BytesCodec::new());
+        let items: Vec<Utf8Bytes> = framed.map(Result::unwrap).collect().await;
+        assert_eq!(items, ["hi", "there", "!"]);
+    }
+
+    #[test]
+    fn utf8_bytes_clone_shares_storage() {
+        let text = Utf8Bytes::from(String::from("공유 텍스트"));
+        let copy = text.clone();
+        assert_eq!(copy.as_ptr(), text.as_ptr());
+        assert_eq!(&*copy, "공유 텍스트");
+        assert_eq!(copy.to_string(), "공유 텍스트");
+    }
+
+    #[test]
+    fn utf8_bytes_rejects_invalid() {
+        let bytes = tokio_util::bytes::Bytes::from_static(&[b'a', 0xFF]);
+        assert!(Utf8Bytes::try_from_bytes(bytes).is_err());
+    }
+
+    #[tokio::test]
+    async fn sse_decoder_with_framed_read() {
+        use tokio_util::codec::FramedRead;
+        use utf8_chunked::SseDecoder;
+
+        // '한' = ED 95 9C split across reads, as in a streaming LLM response
+        let chunks: Vec<Result<tokio_util::bytes::Bytes, std::io::Error>> = vec![
+            Ok(tokio_util::bytes::Bytes::from_static(b"data: {\"t\":\"\xED")),
+            Ok(tokio_util::bytes::Bytes::from_static(b"\x95\x9C\"}\r\n\r")),
+            Ok(tokio_util::bytes::Bytes::from_static(b"\ndata: [DONE]\n\n")),
+        ];
+
+        let stream = tokio_stream::iter(chunks);
+        let reader = tokio_util::io::StreamReader::new(stream);
+        let framed = FramedRead::new(reader, SseDecoder::new());
+        let data: Vec<String> = framed.map(|e| e.unwrap().data).collect().await;
+
+        assert_eq!(data, ["{\"t\":\"한\"}", "[DONE]"]);
+    }
+
+    #[tokio::test]
+    async fn ndjson_decoder_with_framed_read() {
+        use tokio_util::codec::FramedRead;
+        use utf8_chunked::NdjsonDecoder;
+
+        // '世' = E4 B8 96 split across reads; last record has no trailing newline
+        let chunks: Vec<Result<tokio_util::bytes::Bytes, std::io::Error>> = vec![
+            Ok(tokio_util::bytes::Bytes::from_static(b"{\"log\":\"\xE4\xB8")),
+            Ok(tokio_util::bytes::Bytes::from_static(b"\x96\"}\r\n\r\n")),
+            Ok(tokio_util::bytes::Bytes::from_static(b"{\"log\":\"end\"}")),
+        ];
+
+        let stream = tokio_stream::iter(chunks);
+        let reader = tokio_util::io::StreamReader::new(stream);
+        let framed = FramedRead::new(reader, NdjsonDecoder::new());
+        let records: Vec<_> = framed.map(Result::unwrap).collect().await;
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].text, "{\"log\":\"世\"}");
+        assert_eq!((records[1].line, records[1].text.as_str()), (3, "{\"log\":\"end\"}"));
+    }
+
+    #[tokio::test]
+    async fn file_follower_joins_split_writes() {
+        use std::io::Write;
+        use std::time::Duration;
+        use utf8_chunked::FileFollower;
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        let mut follower = FileFollower::new(file.path())
+            .from_start(true)
+            .poll_interval(Duration::from_millis(5));
+
+        // The writer flushes half of '한' (ED 95 9C) before the next write
+        file.write_all(b"log \xED\x95").unwrap();
+        file.flush().unwrap();
+        assert_eq!(follower.next_text().await.unwrap(), "log ");
+
+        file.write_all(b"\x9C\n").unwrap();
+        file.flush().unwrap();
+        assert_eq!(follower.next_text().await.unwrap(), "한\n");
+    }
+
+    #[tokio::test]
+    async fn file_follower_delivers_backlog_in_bounded_pieces() {
+        use std::io::Write;
+        use std::time::Duration;
+        use utf8_chunked::FileFollower;
+
+        let backlog = "로그 line 🦀\n".repeat(10_000);
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(backlog.as_bytes()).unwrap();
+        file.flush().unwrap();
+
+        let mut follower = FileFollower::new(file.path())
+            .from_start(true)
+            .poll_interval(Duration::from_secs(60));
+        let mut text = String::new();
+        let mut pieces = 0;
+        while text.len() < backlog.len() {
+            let piece = follower.next_text().await.unwrap();
+            assert!(piece.len() <= 16 * 1024, "piece of {} bytes", piece.len());
+            text.push_str(&piece);
+            pieces += 1;
+        }
+        assert_eq!(text, backlog);
+        assert!(pieces > 1);
+    }
+
+    #[tokio::test]
+    async fn file_follower_starts_at_end_and_handles_truncation() {
+        use std::io::{Seek, Write};
+        use std::time::Duration;
+        use utf8_chunked::FileFollower;
+
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all("old contents 세계\n".as_bytes()).unwrap();
+        file.flush().unwrap();
+
+        let mut follower = FileFollower::new(file.path()).poll_interval(Duration::from_millis(5));
+        let next = tokio::spawn(async move {
+            let text = follower.next_text().await.unwrap();
+            (text, follower)
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        file.write_all(b"new\xE4").unwrap();
+        file.flush().unwrap();
+        let (text, mut follower) = next.await.unwrap();
+        assert_eq!(text, "new");
+
+        // copytruncate-style rotation: reading starts over from offset 0
+        file.as_file().set_len(0).unwrap();
+        file.as_file_mut().rewind().unwrap();
+        file.write_all(b"ok").unwrap();
+        file.flush().unwrap();
+        assert_eq!(follower.next_text().await.unwrap(), "ok");
+        assert_eq!(follower.position(), 2);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn file_follower_switches_to_rotated_file() {
+        use std::io::Write;
+        use std::time::Duration;
+        use utf8_chunked::FileFollower;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.log");
+        std::fs::write(&path, b"first\n\xF0\x9F").unwrap();
+
+        let mut follower = FileFollower::new(&path)
+            .from_start(true)
+            .poll_interval(Duration::from_millis(5));
+        assert_eq!(follower.next_text().await.unwrap(), "first\n");
+
+        std::fs::rename(&path, dir.path().join("app.log.1")).unwrap();
+        let mut new_file = std::fs::File::create(&path).unwrap();
+        new_file.write_all(b"second\n").unwrap();
+
+        assert_eq!(follower.next_text().await.unwrap(), "second\n");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn child_output_keeps_streams_apart() {
+        use tokio::process::Command;
+        use utf8_chunked::{spawn_output, ChildEvent, StreamKind};
+
+        // '한' = ED 95 9C is split across two writes on stdout, with stderr
+        // output in between
+        let script = r"printf '\355\225'; sleep 0.05; printf 'warn' >&2; sleep 0.05; printf '\234\n'; exit 3";
+        let events: Vec<ChildEvent> = spawn_output(Command::new("sh").args(["-c", script]))
+            .unwrap()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+
+        let text = |kind| {
+            events
+                .iter()
+                .filter_map(|e| match e {
+                    ChildEvent::Output(k, text) if *k == kind => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect::<String>()
+        };
+        assert_eq!(text(StreamKind::Stdout), "한\n");
+        assert_eq!(text(StreamKind::Stderr), "warn");
+        match events.last() {
+            Some(ChildEvent::Exit(status)) => assert_eq!(status.code(), Some(3)),
+            other => panic!("expected exit status last, got {other:?}"),
+        }
+    }
+
+    #[cfg(all(feature = "pty", target_os = "linux"))]
+    #[tokio::test]
+    async fn pty_output_is_a_tty_and_keeps_characters_whole() {
+        use tokio::process::Command;
+        use utf8_chunked::{spawn_pty, PtyOptions};
+
+        // '한' = ED 95 9C is split across two writes
+        let script = r"test -t 1 && printf 'tty \355\225'; sleep 0.05; printf '\234\n'";
+        let mut command = Command::new("sh");
+        command.args(["-c", script]);
+        let (mut process, output) = spawn_pty(&mut command, PtyOptions::default()).unwrap();
+        let text: String = output.map(Result::unwrap).collect().await;
+        assert_eq!(text, "tty 한\n");
+        assert!(process.wait().await.unwrap().success());
+    }
+
+    #[cfg(all(feature = "pty", target_os = "linux"))]
+    #[tokio::test]
+    async fn pty_crlf_and_window_size() {
+        use tokio::process::Command;
+        use utf8_chunked::{spawn_pty, PtyOptions, PtySize};
+
+        let options = PtyOptions {
+            size: PtySize { rows: 40, cols: 100 },
+            crlf: true,
+        };
+        // Report the size again once SIGWINCH arrives; the trap is set before
+        // the first report, so resizing after reading it cannot be missed
+        let script = "trap 'stty size; exit 0' WINCH; stty size; while :; do sleep 0.01; done";
+        let mut command = Command::new("sh");
+        command.args(["-c", script]);
+        let (mut process, output) = spawn_pty(&mut command, options).unwrap();
+        tokio::pin!(output);
+
+        let mut text = String::new();
+        while !text.ends_with('\n') {
+            text.push_str(&output.next().await.unwrap().unwrap());
+        }
+        assert_eq!(text, "40 100\r\n");
+        process.resize(PtySize { rows: 50, cols: 132 }).unwrap();
+        let rest: String = output.map(Result::unwrap).collect().await;
+        assert_eq!(rest, "50 132\r\n");
+        assert!(process.wait().await.unwrap().success());
+    }
+
+    #[tokio::test]
+    async fn broadcast_replays_recent_text_to_late_subscribers() {
+        use tokio::io::AsyncWriteExt;
+        use utf8_chunked::{TextBroadcaster, TextEvent};
+
+        let (mut writer, reader) = tokio::io::duplex(64);
+        let broadcaster = TextBroadcaster::spawn(reader, 16, 6);
+        let mut early = broadcaster.subscribe();
+
+        // '한' = ED 95 9C is split across two writes
+        writer.write_all(b"aa\xED\x95").await.unwrap();
+        assert_eq!(early.recv().await, Some(TextEvent::Text("aa".into())));
+        writer.write_all(b"\x9Cbb").await.unwrap();
+        // The completed character is its own piece, ahead of the rest
+        assert_eq!(early.recv().await, Some(TextEvent::Text("한".into())));
+        assert_eq!(early.recv().await, Some(TextEvent::Text("bb".into())));
+
+        // "aa" no longer fits in the 6-byte replay buffer
+        let mut late = broadcaster.subscribe();
+        assert_eq!(late.recv().await, Some(TextEvent::Text("한".into())));
+        assert_eq!(late.recv().await, Some(TextEvent::Text("bb".into())));
+
+        drop(writer);
+        assert_eq!(early.recv().await, None);
+        assert_eq!(late.recv().await, None);
+        broadcaster.join().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn broadcast_reports_lag_and_ends_after_source() {
+        use tokio::io::AsyncWriteExt;
+        use utf8_chunked::{TextBroadcaster, TextEvent};
+
+        let (mut writer, reader) = tokio::io::duplex(64);
+        let broadcaster = TextBroadcaster::spawn(reader, 2, 1024);
+        let mut fast = broadcaster.subscribe();
+        let mut slow = broadcaster.subscribe();
+
+        for piece in ["a", "b", "c", "d"] {
+            writer.write_all(piece.as_bytes()).await.unwrap();
+            assert_eq!(fast.recv().await, Some(TextEvent::Text(piece.into())));
+        }
+        drop(writer);
+
+        assert_eq!(slow.recv().await, Some(TextEvent::Lagged(2)));
+        assert_eq!(slow.recv().await, Some(TextEvent::Text("c".into())));
+        assert_eq!(slow.recv().await, Some(TextEvent::Text("d".into())));
+        assert_eq!(slow.recv().await, None);
+
+        while !broadcaster.is_finished() {
+            tokio::task::yield_now().await;
+        }
+        let mut after_end = broadcaster.subscribe();
+        let mut replay = String::new();
+        while let Some(TextEvent::Text(text)) = after_end.recv().await {
+            replay.push_str(&text);
+        }
+        assert_eq!(replay, "abcd");
+        broadcaster.join().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn broadcast_shares_text_and_stops_on_drop() {
+        use tokio::io::AsyncWriteExt;
+        use utf8_chunked::{TextBroadcaster, TextEvent};
+
+        let (mut writer, reader) = tokio::io::duplex(64);
+        let broadcaster = TextBroadcaster::spawn(reader, 16, 1024);
+        let mut a = broadcaster.subscribe();
+        let mut b = broadcaster.subscribe();
+
+        writer.write_all("공유".as_bytes()).await.unwrap();
+        let (Some(TextEvent::Text(x)), Some(TextEvent::Text(y))) = (a.recv().await, b.recv().await)
+        else {
+            panic!("expected text");
+        };
+        assert_eq!(x, "공유");
+        assert_eq!(x.as_ptr(), y.as_ptr());
+
+        // The source is still open, but dropping the broadcaster ends the text
+        drop(broadcaster);
+        assert_eq!(a.recv().await, None);
+        writer.write_all(b"late").await.unwrap_err();
+    }
+
+    #[cfg(all(feature = "pty", target_os = "linux"))]
+    #[tokio::test]
+    async fn pty_writer_sends_input() {
+        use tokio::io::AsyncWriteExt;
+        use tokio::process::Command;
+        use utf8_chunked::{spawn_pty, PtyOptions};
+
+        let script = r#"stty -echo; echo ready; read line; echo "got $line""#;
+        let mut command = Command::new("sh");
+        command.args(["-c", script]);
+        let (mut process, output) = spawn_pty(&mut command, PtyOptions::default()).unwrap();
+        tokio::pin!(output);
+
+        // Echo is off once "ready" arrives, so the input is not repeated
+        let mut text = String::new();
+        while !text.ends_with('\n') {
+            text.push_str(&output.next().await.unwrap().unwrap());
+        }
+        assert_eq!(text, "ready\n");
+        let mut writer = process.writer().unwrap();
+        writer.write_all(b"\xED\x95\x9C\xED").await.unwrap();
+        writer.write_all(b"\x95\x9C\n").await.unwrap();
+        let rest: String = output.map(Result::unwrap).collect().await;
+        assert_eq!(rest, "got 한한\n");
+        assert!(process.wait().await.unwrap().success());
+    }
 }